@@ -0,0 +1,261 @@
+//! Known answers for regression checking.
+//!
+//! Answers are kept in a plain text file with one answer per line in the form
+//! `<day> <part> <answer>`. Blank lines and lines starting with `#` are
+//! ignored.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use runner::Run;
+use solutions::{DisplayPart, Part};
+
+/// The default answers file, found in whichever directory the command is run
+/// from.
+pub const ANSWERS_FILE: &str = "answers.txt";
+
+/// Recorded answers keyed by day and part.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Answers {
+    known: BTreeMap<(u32, u8), String>,
+}
+
+impl Answers {
+    /// Parse the contents of an answers file.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut answers = Answers::default();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let bad_line = || format!("line {}: expected `<day> <part> <answer>`, got {:?}", i + 1, line);
+            let mut fields = line.splitn(3, char::is_whitespace);
+            let day = fields
+                .next()
+                .and_then(|d| d.parse().ok())
+                .ok_or_else(bad_line)?;
+            let part = fields
+                .next()
+                .and_then(|p| p.parse().ok())
+                .filter(|p| *p == 1 || *p == 2)
+                .ok_or_else(bad_line)?;
+            let answer = fields
+                .next()
+                .map(str::trim)
+                .filter(|a| !a.is_empty())
+                .ok_or_else(bad_line)?;
+
+            answers.set(day, part, answer);
+        }
+
+        Ok(answers)
+    }
+
+    /// Load the answers file at `path`, which is treated as empty if missing.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        match fs::read_to_string(path) {
+            Ok(text) => Answers::parse(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(format!("could not read {}: {}", path.display(), e)),
+        }
+    }
+
+    /// Write the answers to `path`, replacing its contents.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        let path = path.as_ref();
+        fs::write(path, self.to_string())
+            .map_err(|e| format!("could not write {}: {}", path.display(), e))
+    }
+
+    pub fn get(&self, day: u32, part: u8) -> Option<&str> {
+        self.known.get(&(day, part)).map(|a| a.as_str())
+    }
+
+    pub fn set(&mut self, day: u32, part: u8, answer: &str) {
+        self.known.insert((day, part), answer.to_string());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.known.is_empty()
+    }
+
+    /// Record every successfully solved part of `runs`, returning how many
+    /// answers were recorded.
+    pub fn record(&mut self, runs: &[Run]) -> usize {
+        let mut recorded = 0;
+        for run in runs {
+            if let Ok(ref answer) = run.answer {
+                for (part, result) in [(1, &answer.part1), (2, &answer.part2)].iter() {
+                    if let Some(Ok(ref a)) = **result {
+                        self.set(run.day, *part, a);
+                        recorded += 1;
+                    }
+                }
+            }
+        }
+
+        recorded
+    }
+
+    /// Compare `runs` against the recorded answers.
+    ///
+    /// Parts without a recorded answer are not checked.
+    pub fn verify(&self, runs: &[Run]) -> Verification {
+        let mut verification = Verification::default();
+        for run in runs {
+            for part in 1..=2 {
+                let expected = match self.get(run.day, part) {
+                    Some(expected) => expected,
+                    None => continue,
+                };
+
+                let actual = match run.answer {
+                    Ok(ref answer) => actual(if part == 1 { &answer.part1 } else { &answer.part2 }),
+                    Err(ref err) => Err(format!("error: {}", err)),
+                };
+                match actual {
+                    Ok(ref a) if a == expected => verification.matched += 1,
+                    Ok(a) | Err(a) => verification.mismatches.push(Mismatch {
+                        day: run.day,
+                        part,
+                        expected: expected.to_string(),
+                        actual: a,
+                    }),
+                }
+            }
+        }
+
+        verification
+    }
+}
+
+/// The answer a part produced, or a description of why there is none.
+fn actual(part: &Part) -> Result<String, String> {
+    match *part {
+        Some(Ok(ref a)) => Ok(a.clone()),
+        _ => Err(DisplayPart(part).to_string()),
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "# <day> <part> <answer>")?;
+        for (&(day, part), answer) in &self.known {
+            writeln!(f, "{} {} {}", day, part, answer)?;
+        }
+        Ok(())
+    }
+}
+
+/// A part whose answer differs from the recorded one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    pub day: u32,
+    pub part: u8,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Day {} part {}: expected {}, got {}",
+            self.day, self.part, self.expected, self.actual
+        )
+    }
+}
+
+/// The outcome of checking runs against recorded answers.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Verification {
+    pub matched: usize,
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl Verification {
+    pub fn passed(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use error::Error;
+    use solutions::{solved, Answer, Parts, Timings};
+
+    fn run(day: u32, part1: Part, part2: Part) -> Run {
+        Run {
+            day,
+            title: "",
+            parts: Parts::Both,
+            answer: Ok(Answer { part1, part2 }),
+            timings: Timings::default(),
+        }
+    }
+
+    #[test]
+    fn parse_and_display_round_trip() {
+        let text = "# <day> <part> <answer>\n1 1 445\n1 2 219\n2 2 kqzxdenujwcstybmgvyiofrrd\n";
+        let answers = Answers::parse(text).unwrap();
+        assert_eq!(Some("445"), answers.get(1, 1));
+        assert_eq!(Some("kqzxdenujwcstybmgvyiofrrd"), answers.get(2, 2));
+        assert_eq!(None, answers.get(2, 1));
+        assert_eq!(text, answers.to_string());
+    }
+
+    #[test]
+    fn parse_rejects_bad_lines() {
+        assert_eq!(
+            Err("line 2: expected `<day> <part> <answer>`, got \"1 3 445\"".to_string()),
+            Answers::parse("\n1 3 445\n")
+        );
+        assert!(Answers::parse("x 1 445").is_err());
+        assert!(Answers::parse("1 1").is_err());
+    }
+
+    #[test]
+    fn record_skips_missing_and_failed_parts() {
+        let mut answers = Answers::default();
+        let runs = vec![
+            run(1, solved(445), None),
+            run(3, Some(Err(Error::no_answer("bad claim"))), solved(506)),
+        ];
+        assert_eq!(2, answers.record(&runs));
+        assert_eq!(Some("445"), answers.get(1, 1));
+        assert_eq!(None, answers.get(1, 2));
+        assert_eq!(None, answers.get(3, 1));
+        assert_eq!(Some("506"), answers.get(3, 2));
+    }
+
+    #[test]
+    fn verify_reports_mismatches() {
+        let answers = Answers::parse("1 1 445\n1 2 219\n3 1 109143\n").unwrap();
+        let runs = vec![
+            run(1, solved(445), solved(220)),
+            run(3, None, solved(506)),
+        ];
+
+        let verification = answers.verify(&runs);
+        assert_eq!(1, verification.matched);
+        assert!(!verification.passed());
+        assert_eq!(
+            vec![
+                "Day 1 part 2: expected 219, got 220".to_string(),
+                "Day 3 part 1: expected 109143, got not implemented".to_string(),
+            ],
+            verification
+                .mismatches
+                .iter()
+                .map(|m| m.to_string())
+                .collect::<Vec<_>>()
+        );
+    }
+}
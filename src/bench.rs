@@ -0,0 +1,213 @@
+//! Repeatedly timing a solution to compare implementations.
+
+use std::time::Duration;
+
+use report::{format_duration, json_part, json_parts, json_string, nanos};
+use solutions::{Answer, Parts, Solution, Timings};
+
+/// Summary statistics over a set of timing samples.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+}
+
+impl Stats {
+    /// Summarise `samples`, which must not be empty.
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "no samples to summarise");
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let mid = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[mid - 1] + sorted[mid]) / 2
+        } else {
+            sorted[mid]
+        };
+        let total: Duration = sorted.iter().sum();
+
+        Stats {
+            min: sorted[0],
+            median,
+            mean: total / sorted.len() as u32,
+        }
+    }
+}
+
+/// Timing statistics for each stage of a day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bench {
+    pub day: u32,
+    pub title: &'static str,
+    pub runs: usize,
+    pub parts: Parts,
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+    /// The answer from the final run, so a broken solver isn't mistaken for
+    /// a fast one.
+    pub answer: Answer,
+}
+
+/// Solve the selected parts of `lines` with `solution` `runs` times.
+pub fn bench(solution: &dyn Solution, lines: &[String], runs: usize, parts: Parts) -> Bench {
+    assert!(runs > 0, "must benchmark at least one run");
+
+    let mut samples: Vec<Timings> = Vec::with_capacity(runs);
+    let mut answer = None;
+    for i in 0..runs {
+        debug!("Day {} run {}/{}", solution.day(), i + 1, runs);
+        let (a, t) = solution.timed(lines, parts);
+        samples.push(t);
+        answer = Some(a);
+    }
+
+    let stats = |stage: fn(&Timings) -> Duration| {
+        Stats::from_samples(&samples.iter().map(stage).collect::<Vec<_>>())
+    };
+
+    Bench {
+        day: solution.day(),
+        title: solution.title(),
+        runs,
+        parts,
+        parse: stats(|t| t.parse),
+        part1: stats(|t| t.part1),
+        part2: stats(|t| t.part2),
+        answer: answer.unwrap(),
+    }
+}
+
+/// Render a benchmark as a small table of statistics per stage.
+pub fn table(bench: &Bench) -> String {
+    let mut out = format!(
+        "Day {}: {} ({} runs)\n{:<6}  {:>10}  {:>10}  {:>10}\n",
+        bench.day, bench.title, bench.runs, "Stage", "Min", "Median", "Mean"
+    );
+    for &(stage, stats, included) in &[
+        ("Parse", bench.parse, true),
+        ("Part 1", bench.part1, bench.parts.includes(1)),
+        ("Part 2", bench.part2, bench.parts.includes(2)),
+    ] {
+        if !included {
+            continue;
+        }
+        out.push_str(&format!(
+            "{:<6}  {:>10}  {:>10}  {:>10}\n",
+            stage,
+            format_duration(stats.min),
+            format_duration(stats.median),
+            format_duration(stats.mean)
+        ));
+    }
+
+    out
+}
+
+/// Describe a benchmark as a JSON object, with timings in nanoseconds.
+///
+/// Parts that weren't asked for are left out of both the timings and the
+/// answer.
+pub fn json(bench: &Bench) -> String {
+    let stats = |s: &Stats| {
+        format!(
+            r#"{{"min_ns":{},"median_ns":{},"mean_ns":{}}}"#,
+            nanos(s.min),
+            nanos(s.median),
+            nanos(s.mean)
+        )
+    };
+
+    format!(
+        r#"{{"day":{},"title":{},"runs":{},"parse":{},{},"answer":{{{}}}}}"#,
+        bench.day,
+        json_string(bench.title),
+        bench.runs,
+        stats(&bench.parse),
+        json_parts(bench.parts, &stats(&bench.part1), &stats(&bench.part2)),
+        json_parts(
+            bench.parts,
+            &json_part(&bench.answer.part1),
+            &json_part(&bench.answer.part2)
+        )
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solutions::day01::Day01;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn stats_odd_samples() {
+        let stats = Stats::from_samples(&[ms(5), ms(1), ms(3)]);
+        assert_eq!(ms(1), stats.min);
+        assert_eq!(ms(3), stats.median);
+        assert_eq!(ms(3), stats.mean);
+    }
+
+    #[test]
+    fn stats_even_samples() {
+        let stats = Stats::from_samples(&[ms(4), ms(1), ms(2), ms(9)]);
+        assert_eq!(ms(1), stats.min);
+        assert_eq!(ms(3), stats.median);
+        assert_eq!(ms(4), stats.mean);
+    }
+
+    #[test]
+    fn bench_json() {
+        let stats = Stats {
+            min: Duration::new(0, 1),
+            median: Duration::new(0, 2),
+            mean: Duration::new(0, 3),
+        };
+        let bench = Bench {
+            day: 7,
+            title: "The Sum of Its Parts",
+            runs: 2,
+            parts: Parts::Both,
+            parse: stats,
+            part1: stats,
+            part2: Stats::default(),
+            answer: Answer {
+                part1: Some(Ok("CABDFE".to_string())),
+                part2: None,
+            },
+        };
+        let expected = concat!(
+            r#"{"day":7,"title":"The Sum of Its Parts","runs":2,"#,
+            r#""parse":{"min_ns":1,"median_ns":2,"mean_ns":3},"#,
+            r#""part1":{"min_ns":1,"median_ns":2,"mean_ns":3},"#,
+            r#""part2":{"min_ns":0,"median_ns":0,"mean_ns":0},"#,
+            r#""answer":{"part1":{"answer":"CABDFE"},"part2":null}}"#
+        );
+        assert_eq!(expected, json(&bench));
+
+        let bench = Bench {
+            parts: Parts::Part1,
+            ..bench
+        };
+        let expected = concat!(
+            r#"{"day":7,"title":"The Sum of Its Parts","runs":2,"#,
+            r#""parse":{"min_ns":1,"median_ns":2,"mean_ns":3},"#,
+            r#""part1":{"min_ns":1,"median_ns":2,"mean_ns":3},"#,
+            r#""answer":{"part1":{"answer":"CABDFE"}}}"#
+        );
+        assert_eq!(expected, json(&bench));
+    }
+
+    #[test]
+    fn bench_keeps_answer() {
+        let lines = vec!["+1".to_string(), "-1".to_string()];
+        let bench = bench(&Day01, &lines, 3, Parts::Both);
+        assert_eq!(3, bench.runs);
+        assert_eq!(Some(Ok("0".to_string())), bench.answer.part1);
+    }
+}
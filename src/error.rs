@@ -0,0 +1,200 @@
+//! The error type shared by every solution and the runner.
+
+use std::error;
+use std::fmt;
+
+/// Why a day could not produce an answer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// A line of input that doesn't match what the day expects.
+    Parse {
+        day: Option<u32>,
+        /// The 1-based line number, when known.
+        line: Option<usize>,
+        text: String,
+        expected: String,
+    },
+    /// Two lines of input that should be the same length but aren't.
+    LengthMismatch {
+        day: Option<u32>,
+        /// The 1-based line number of `right`, when known.
+        line: Option<usize>,
+        left: String,
+        right: String,
+    },
+    /// The input was understood but has no answer.
+    NoAnswer { day: Option<u32>, reason: String },
+    /// The solver panicked.
+    Panic { day: Option<u32>, message: String },
+    /// The input could not be read.
+    Input { path: Option<String>, reason: String },
+}
+
+impl Error {
+    /// `text` was not what was `expected`.
+    pub fn parse<T: Into<String>, E: Into<String>>(text: T, expected: E) -> Self {
+        Error::Parse {
+            day: None,
+            line: None,
+            text: text.into(),
+            expected: expected.into(),
+        }
+    }
+
+    /// `right` has a different number of characters from `left`.
+    pub fn length_mismatch<L: Into<String>, R: Into<String>>(left: L, right: R) -> Self {
+        Error::LengthMismatch {
+            day: None,
+            line: None,
+            left: left.into(),
+            right: right.into(),
+        }
+    }
+
+    pub fn no_answer<R: Into<String>>(reason: R) -> Self {
+        Error::NoAnswer {
+            day: None,
+            reason: reason.into(),
+        }
+    }
+
+    pub fn panic<M: Into<String>>(message: M) -> Self {
+        Error::Panic {
+            day: None,
+            message: message.into(),
+        }
+    }
+
+    /// Reading the input at `path`, or stdin if there is none, failed.
+    pub fn input<E: fmt::Display>(path: Option<&str>, err: E) -> Self {
+        Error::Input {
+            path: path.map(String::from),
+            reason: err.to_string(),
+        }
+    }
+
+    /// Attach the 1-based line number a parse error or length mismatch
+    /// happened on, unless one is already known.
+    pub fn at_line(mut self, n: usize) -> Self {
+        match self {
+            Error::Parse { ref mut line, .. } | Error::LengthMismatch { ref mut line, .. } => {
+                line.get_or_insert(n);
+            }
+            _ => {}
+        }
+        self
+    }
+
+    /// Attach the day the error happened in, unless one is already known.
+    pub fn in_day(mut self, n: u32) -> Self {
+        match self {
+            Error::Parse { ref mut day, .. }
+            | Error::LengthMismatch { ref mut day, .. }
+            | Error::NoAnswer { ref mut day, .. }
+            | Error::Panic { ref mut day, .. } => {
+                day.get_or_insert(n);
+            }
+            Error::Input { .. } => {}
+        }
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Parse {
+                day,
+                line,
+                ref text,
+                ref expected,
+            } => {
+                location(f, day, line)?;
+                write!(f, "expected {}, got {:?}", expected, text)
+            }
+            Error::LengthMismatch {
+                day,
+                line,
+                ref left,
+                ref right,
+            } => {
+                location(f, day, line)?;
+                write!(
+                    f,
+                    "{:?} and {:?} are different lengths, {} and {} characters",
+                    left,
+                    right,
+                    left.chars().count(),
+                    right.chars().count()
+                )
+            }
+            Error::NoAnswer { day, ref reason } => {
+                if let Some(day) = day {
+                    write!(f, "day {}: ", day)?;
+                }
+                write!(f, "{}", reason)
+            }
+            Error::Panic { day, ref message } => {
+                if let Some(day) = day {
+                    write!(f, "day {}: ", day)?;
+                }
+                write!(f, "panicked: {}", message)
+            }
+            Error::Input {
+                ref path,
+                ref reason,
+            } => match *path {
+                Some(ref path) => write!(f, "could not read {}: {}", path, reason),
+                None => write!(f, "could not read input: {}", reason),
+            },
+        }
+    }
+}
+
+/// The `day 3 line 4: ` prefix for whichever of the two are known.
+fn location(f: &mut fmt::Formatter, day: Option<u32>, line: Option<usize>) -> fmt::Result {
+    match (day, line) {
+        (Some(day), Some(line)) => write!(f, "day {} line {}: ", day, line),
+        (Some(day), None) => write!(f, "day {}: ", day),
+        (None, Some(line)) => write!(f, "line {}: ", line),
+        (None, None) => Ok(()),
+    }
+}
+
+impl error::Error for Error {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_display() {
+        let err = Error::parse("#1 @ 1,x", "a claim");
+        assert_eq!(r##"expected a claim, got "#1 @ 1,x""##, err.to_string());
+        let err = err.at_line(4);
+        assert_eq!(r##"line 4: expected a claim, got "#1 @ 1,x""##, err.to_string());
+        let err = err.in_day(3).at_line(5);
+        assert_eq!(
+            r##"day 3 line 4: expected a claim, got "#1 @ 1,x""##,
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn other_display() {
+        assert_eq!(
+            "day 3: no lone claim",
+            Error::no_answer("no lone claim").in_day(3).to_string()
+        );
+        assert_eq!("panicked: oops", Error::panic("oops").to_string());
+        assert_eq!(
+            r#"day 2 line 3: "naïve" and "nave" are different lengths, 5 and 4 characters"#,
+            Error::length_mismatch("naïve", "nave").at_line(3).in_day(2).to_string()
+        );
+        assert_eq!(
+            "could not read input/day04.txt: missing",
+            Error::input(Some("input/day04.txt"), "missing").in_day(4).to_string()
+        );
+        assert_eq!("could not read input: closed", Error::input(None, "closed").to_string());
+    }
+}
@@ -0,0 +1,234 @@
+//! Random puzzle inputs for stress testing, reproducible from a seed.
+//!
+//! Generated inputs always follow the day's format, but like real inputs
+//! aren't guaranteed to have an answer for every part.
+
+use std::collections::BTreeSet;
+
+/// A small xorshift pseudo random number generator, good enough for inputs
+/// and stable across platforms and versions.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // xorshift never leaves zero, so mix the seed into a nonzero state
+        let state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ 0x2545_F491_4F6C_DD1D;
+        Rng(if state == 0 { 1 } else { state })
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A number in `0..n`, which must not be empty.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// A number in `low..=high`.
+    pub fn between(&mut self, low: isize, high: isize) -> isize {
+        low + self.below((high - low) as usize + 1) as isize
+    }
+
+    /// True with probability `1 / n`.
+    pub fn one_in(&mut self, n: usize) -> bool {
+        self.below(n) == 0
+    }
+}
+
+/// The size used when none is given, close to that of the real inputs.
+pub fn default_size(day: u32) -> Option<usize> {
+    match day {
+        1 => Some(1000),
+        3 => Some(1300),
+        5 => Some(50_000),
+        7 => Some(26),
+        _ => None,
+    }
+}
+
+/// An input for `day` of roughly `size` lines or units, see the per-day
+/// generators for what `size` means.
+///
+/// `size` must be at least 1, since no day accepts an empty input.
+pub fn generate(day: u32, size: usize, seed: u64) -> Result<Vec<String>, String> {
+    if size == 0 {
+        return Err("size must be at least 1".to_string());
+    }
+    let mut rng = Rng::new(seed);
+    match day {
+        1 => Ok(frequencies(&mut rng, size)),
+        3 => Ok(claims(&mut rng, size, 1000)),
+        5 => Ok(vec![polymer(&mut rng, size)]),
+        7 => steps(&mut rng, size),
+        _ => Err(format!("no input generator for day {}", day)),
+    }
+}
+
+/// `count` frequency changes that add up to a small drift, so the running
+/// frequency wanders but usually repeats within a few passes.
+pub fn frequencies(rng: &mut Rng, count: usize) -> Vec<String> {
+    let mut changes: Vec<isize> = (0..count)
+        .map(|_| match rng.between(-20, 20) {
+            0 => 1,
+            n => n,
+        }).collect();
+
+    if let Some(last) = changes.len().checked_sub(1) {
+        let others: isize = changes[..last].iter().sum();
+        let drift = rng.between(-5, 5);
+        changes[last] = drift - others;
+    }
+
+    changes.iter().map(|c| format!("{:+}", c)).collect()
+}
+
+/// `count` claims on a square of fabric `fabric` inches wide.
+pub fn claims(rng: &mut Rng, count: usize, fabric: usize) -> Vec<String> {
+    (1..=count)
+        .map(|id| {
+            let width = 1 + rng.below(fabric.clamp(1, 30));
+            let height = 1 + rng.below(fabric.clamp(1, 30));
+            let left = rng.below(fabric.saturating_sub(width) + 1);
+            let top = rng.below(fabric.saturating_sub(height) + 1);
+            format!("#{} @ {},{}: {}x{}", id, left, top, width, height)
+        }).collect()
+}
+
+/// A polymer of `len` units, where units often follow their opposite so
+/// there is plenty to react.
+pub fn polymer(rng: &mut Rng, len: usize) -> String {
+    let mut polymer = String::with_capacity(len);
+    let mut last: Option<char> = None;
+    for _ in 0..len {
+        let unit = match last {
+            Some(c) if rng.one_in(3) => {
+                if c.is_ascii_lowercase() {
+                    c.to_ascii_uppercase()
+                } else {
+                    c.to_ascii_lowercase()
+                }
+            }
+            _ => {
+                let c = (b'a' + rng.below(26) as u8) as char;
+                if rng.one_in(2) {
+                    c.to_ascii_uppercase()
+                } else {
+                    c
+                }
+            }
+        };
+        polymer.push(unit);
+        last = Some(unit);
+    }
+    polymer
+}
+
+/// Instructions for `count` steps, from 2 to 26, laid out in a few wide layers
+/// where each step waits on one to three steps from earlier layers.
+pub fn steps(rng: &mut Rng, count: usize) -> Result<Vec<String>, String> {
+    if !(2..=26).contains(&count) {
+        return Err(format!(
+            "steps are single letters in pairs so there must be 2 to 26, not {}",
+            count
+        ));
+    }
+
+    let mut letters: Vec<char> = (b'A'..=b'Z').map(|b| b as char).collect();
+    for i in (1..letters.len()).rev() {
+        letters.swap(i, rng.below(i + 1));
+    }
+    letters.truncate(count);
+
+    // each layer is as wide as there are layers, give or take
+    let mut width = 1;
+    while width * width < count {
+        width += 1;
+    }
+
+    let mut edges = BTreeSet::new();
+    for (i, &after) in letters.iter().enumerate().skip(width) {
+        let layer_start = i / width * width;
+        for _ in 0..1 + rng.below(3) {
+            edges.insert((letters[rng.below(layer_start)], after));
+        }
+    }
+
+    // a step only exists if an instruction mentions it, and edges always run
+    // from earlier to later in `letters` so there are no cycles
+    for i in 0..count {
+        let step = letters[i];
+        if !edges.iter().any(|&(a, b)| a == step || b == step) {
+            let j = (i + 1 + rng.below(count - 1)) % count;
+            edges.insert((letters[i.min(j)], letters[i.max(j)]));
+        }
+    }
+
+    Ok(edges
+        .into_iter()
+        .map(|(a, b)| format!("Step {} must be finished before step {} can begin.", a, b))
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solutions;
+
+    fn check(day: u32, size: usize, seed: u64) -> Vec<String> {
+        let lines = generate(day, size, seed).unwrap();
+        let errors = solutions::get(day).unwrap().check(&lines);
+        assert!(errors.is_empty(), "day {}: {:?}", day, errors);
+        lines
+    }
+
+    #[test]
+    fn generated_inputs_are_valid() {
+        for seed in 0..5 {
+            assert_eq!(500, check(1, 500, seed).len());
+            assert_eq!(500, check(3, 500, seed).len());
+            assert_eq!(10_000, check(5, 10_000, seed)[0].len());
+            check(7, 26, seed);
+            check(7, 5, seed);
+        }
+    }
+
+    #[test]
+    fn smallest_inputs_are_valid() {
+        for &day in &[1, 3, 5] {
+            assert_eq!(1, check(day, 1, 0).len());
+            assert!(generate(day, 0, 0).is_err());
+        }
+    }
+
+    #[test]
+    fn same_seed_same_input() {
+        assert_eq!(generate(3, 100, 7), generate(3, 100, 7));
+        assert_ne!(generate(3, 100, 7), generate(3, 100, 8));
+    }
+
+    #[test]
+    fn dags_have_every_step() {
+        let lines = check(7, 26, 1);
+        let steps = solutions::day07::parse_input(&lines).unwrap();
+        assert_eq!(26, steps.len());
+        assert!(solutions::day07::solve(&steps).is_ok());
+    }
+
+    #[test]
+    fn frequencies_drift_slightly() {
+        let changes: Vec<isize> = check(1, 200, 3).iter().map(|c| c.parse().unwrap()).collect();
+        assert!(changes.iter().sum::<isize>().abs() <= 5);
+    }
+
+    #[test]
+    fn unsupported() {
+        assert!(generate(2, 10, 0).is_err());
+        assert!(generate(7, 27, 0).is_err());
+        assert!(generate(7, 1, 0).is_err());
+    }
+}
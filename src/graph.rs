@@ -0,0 +1,274 @@
+//! Directed graphs of dependencies between nodes, such as steps that must be
+//! finished before others can start.
+//!
+//! An edge from `a` to `b` means `a` must come before `b`. Nodes are kept in
+//! order so every query gives the same answer on every run.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Graph<N: Ord> {
+    /// The nodes each node must come after.
+    dependencies: BTreeMap<N, BTreeSet<N>>,
+    /// The nodes that must come after each node.
+    dependents: BTreeMap<N, BTreeSet<N>>,
+}
+
+/// How to choose between nodes that are ready at the same time.
+pub enum TieBreak<'a, N: 'a> {
+    /// Smallest node first.
+    Lexicographic,
+    /// Largest node first.
+    Reverse,
+    /// Lowest priority first, with equal priorities smallest node first.
+    Priority(&'a dyn Fn(&N) -> i64),
+}
+
+/// Nodes that depend on each other, in edge order, with the first node
+/// repeated at the end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<N>(pub Vec<N>);
+
+impl<N: fmt::Display> fmt::Display for Cycle<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, n) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, " -> ")?;
+            }
+            write!(f, "{}", n)?;
+        }
+        Ok(())
+    }
+}
+
+impl<N: Ord + Clone> Default for Graph<N> {
+    fn default() -> Self {
+        Graph {
+            dependencies: BTreeMap::new(),
+            dependents: BTreeMap::new(),
+        }
+    }
+}
+
+impl<N: Ord + Clone> Graph<N> {
+    pub fn new() -> Self {
+        Graph::default()
+    }
+
+    /// Add a node with no edges, if it isn't already in the graph.
+    pub fn add_node(&mut self, node: N) {
+        self.dependents.entry(node.clone()).or_default();
+        self.dependencies.entry(node).or_default();
+    }
+
+    /// Add an edge saying `before` must come before `after`, adding either
+    /// node if needed.
+    pub fn add_edge(&mut self, before: N, after: N) {
+        self.add_node(before.clone());
+        self.add_node(after.clone());
+        self.dependents.get_mut(&before).unwrap().insert(after.clone());
+        self.dependencies.get_mut(&after).unwrap().insert(before);
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.dependencies.contains_key(node)
+    }
+
+    pub fn len(&self) -> usize {
+        self.dependencies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dependencies.is_empty()
+    }
+
+    /// Every node, smallest first.
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.dependencies.keys()
+    }
+
+    /// The nodes that must come directly before `node`.
+    pub fn dependencies(&self, node: &N) -> impl Iterator<Item = &N> {
+        self.dependencies.get(node).into_iter().flatten()
+    }
+
+    /// The nodes that must come directly after `node`.
+    pub fn dependents(&self, node: &N) -> impl Iterator<Item = &N> {
+        self.dependents.get(node).into_iter().flatten()
+    }
+
+    /// Every node that must come before `node`, directly or not.
+    pub fn ancestors(&self, node: &N) -> BTreeSet<N> {
+        walk(&self.dependencies, node)
+    }
+
+    /// Every node that must come after `node`, directly or not.
+    pub fn descendants(&self, node: &N) -> BTreeSet<N> {
+        walk(&self.dependents, node)
+    }
+
+    /// Whether there is a path of edges from `from` to `to`.
+    pub fn reaches(&self, from: &N, to: &N) -> bool {
+        self.descendants(from).contains(to)
+    }
+
+    /// Every node, each after all its dependencies, choosing between ready
+    /// nodes with `tie_break`.
+    ///
+    /// Fails with one of the cycles if some nodes can never be ready.
+    pub fn topological_sort(&self, tie_break: TieBreak<N>) -> Result<Vec<N>, Cycle<N>> {
+        let mut waiting: BTreeMap<&N, usize> = self
+            .dependencies
+            .iter()
+            .map(|(n, deps)| (n, deps.len()))
+            .collect();
+        let mut ready: BTreeSet<&N> = waiting
+            .iter()
+            .filter(|&(_, &count)| count == 0)
+            .map(|(&n, _)| n)
+            .collect();
+        let mut order = Vec::with_capacity(self.len());
+
+        while let Some(next) = pick(&ready, &tie_break) {
+            ready.remove(next);
+            waiting.remove(next);
+            order.push(next.clone());
+            for after in self.dependents(next) {
+                let count = waiting.get_mut(after).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    ready.insert(after);
+                }
+            }
+        }
+
+        match waiting.keys().next() {
+            Some(&start) => Err(self.cycle_from(start, &waiting)),
+            None => Ok(order),
+        }
+    }
+
+    /// A cycle through nodes that are still `waiting` for a dependency, found
+    /// by following waiting dependencies back from `start` until one repeats.
+    fn cycle_from(&self, start: &N, waiting: &BTreeMap<&N, usize>) -> Cycle<N> {
+        let mut path = vec![start];
+        loop {
+            let current = *path.last().unwrap();
+            let dep = self
+                .dependencies(current)
+                .find(|d| waiting.contains_key(d))
+                .expect("a waiting node has a waiting dependency");
+            if let Some(i) = path.iter().position(|&n| n == dep) {
+                // the path runs backwards along edges, so flip it and start
+                // the cycle from its smallest node
+                let mut cycle: Vec<N> = path[i..].iter().rev().map(|&n| n.clone()).collect();
+                let smallest = (0..cycle.len()).min_by_key(|&j| &cycle[j]).unwrap();
+                cycle.rotate_left(smallest);
+                let first = cycle[0].clone();
+                cycle.push(first);
+                return Cycle(cycle);
+            }
+            path.push(dep);
+        }
+    }
+}
+
+/// The ready node `tie_break` chooses, if any are ready.
+fn pick<'n, N: Ord>(ready: &BTreeSet<&'n N>, tie_break: &TieBreak<N>) -> Option<&'n N> {
+    match *tie_break {
+        TieBreak::Lexicographic => ready.iter().next().cloned(),
+        TieBreak::Reverse => ready.iter().next_back().cloned(),
+        TieBreak::Priority(priority) => ready.iter().min_by_key(|n| priority(n)).cloned(),
+    }
+}
+
+/// Every node reachable from `start` through `edges`, not including `start`
+/// unless it's on a cycle.
+fn walk<N: Ord + Clone>(edges: &BTreeMap<N, BTreeSet<N>>, start: &N) -> BTreeSet<N> {
+    let mut seen = BTreeSet::new();
+    let mut stack = vec![start];
+    while let Some(node) = stack.pop() {
+        for next in edges.get(node).into_iter().flatten() {
+            if seen.insert(next.clone()) {
+                stack.push(next);
+            }
+        }
+    }
+    seen
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// The graph from the day 7 example.
+    fn steps() -> Graph<char> {
+        let mut graph = Graph::new();
+        for &(before, after) in &[
+            ('C', 'A'),
+            ('C', 'F'),
+            ('A', 'B'),
+            ('A', 'D'),
+            ('B', 'E'),
+            ('D', 'E'),
+            ('F', 'E'),
+        ] {
+            graph.add_edge(before, after);
+        }
+        graph
+    }
+
+    fn sorted(graph: &Graph<char>, tie_break: TieBreak<char>) -> String {
+        graph.topological_sort(tie_break).unwrap().into_iter().collect()
+    }
+
+    #[test]
+    fn tie_breaks() {
+        let graph = steps();
+        assert_eq!("CABDFE", sorted(&graph, TieBreak::Lexicographic));
+        assert_eq!("CFADBE", sorted(&graph, TieBreak::Reverse));
+        let d_first = |n: &char| if *n == 'D' { 0 } else { 1 };
+        assert_eq!("CADBFE", sorted(&graph, TieBreak::Priority(&d_first)));
+    }
+
+    #[test]
+    fn reports_cycle() {
+        let mut graph = steps();
+        graph.add_edge('E', 'A');
+        assert_eq!(
+            Err(Cycle(vec!['A', 'B', 'E', 'A'])),
+            graph.topological_sort(TieBreak::Lexicographic)
+        );
+        assert_eq!("A -> B -> E -> A", Cycle(vec!['A', 'B', 'E', 'A']).to_string());
+    }
+
+    #[test]
+    fn self_loop() {
+        let mut graph = Graph::new();
+        graph.add_edge(1, 2);
+        graph.add_edge(2, 2);
+        assert_eq!(Err(Cycle(vec![2, 2])), graph.topological_sort(TieBreak::Lexicographic));
+    }
+
+    #[test]
+    fn reachability() {
+        let graph = steps();
+        assert!(graph.reaches(&'C', &'E'));
+        assert!(!graph.reaches(&'B', &'D'));
+        assert!(!graph.reaches(&'E', &'C'));
+        assert_eq!("ABCDF", graph.ancestors(&'E').into_iter().collect::<String>());
+        assert_eq!("BDE", graph.descendants(&'A').into_iter().collect::<String>());
+        assert_eq!(vec![&'B', &'D', &'F'], graph.dependencies(&'E').collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn lone_nodes() {
+        let mut graph = Graph::new();
+        graph.add_node("b");
+        graph.add_node("a");
+        assert_eq!(2, graph.len());
+        assert_eq!(Ok(vec!["a", "b"]), graph.topological_sort(TieBreak::Lexicographic));
+        assert_eq!(0, graph.dependents(&"c").count());
+    }
+}
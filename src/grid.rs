@@ -0,0 +1,247 @@
+//! A fixed size 2D grid, for puzzles that happen on a plane.
+//!
+//! Positions are `(x, y)` pairs with `x` the column and `y` the row, counting
+//! from the top left corner.
+
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// A `width` by `height` grid with every cell set to `fill`.
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Change the size of the grid, keeping every cell that's still inside
+    /// it where it was and setting new cells to `fill`.
+    pub fn resize(&mut self, width: usize, height: usize, fill: T) {
+        let mut cells = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                cells.push(self.get(x, y).cloned().unwrap_or_else(|| fill.clone()));
+            }
+        }
+
+        self.width = width;
+        self.height = height;
+        self.cells = cells;
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if self.contains(x, y) {
+            Some(&self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if self.contains(x, y) {
+            Some(&mut self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i % width, i / width), cell))
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        if y < self.height {
+            Some(&self.cells[y * self.width..(y + 1) * self.width])
+        } else {
+            None
+        }
+    }
+
+    /// The cells in column `x`, top to bottom, which is empty if `x` is out
+    /// of bounds.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        let height = if x < self.width { self.height } else { 0 };
+        (0..height).map(move |y| &self.cells[y * self.width + x])
+    }
+
+    /// Every row, top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(move |y| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    /// Every column, left to right.
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// The positions above, left, right and below `(x, y)` that are inside
+    /// the grid.
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.offsets(x, y, &[(0, -1), (-1, 0), (1, 0), (0, 1)])
+    }
+
+    /// The positions around `(x, y)`, including diagonals, that are inside
+    /// the grid.
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.offsets(
+            x,
+            y,
+            &[(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)],
+        )
+    }
+
+    fn offsets(
+        &self,
+        x: usize,
+        y: usize,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> {
+        let (width, height) = (self.width, self.height);
+        offsets.iter().filter_map(move |&(dx, dy)| {
+            let nx = x.checked_add_signed(dx).filter(|&nx| nx < width)?;
+            let ny = y.checked_add_signed(dy).filter(|&ny| ny < height)?;
+            Some((nx, ny))
+        })
+    }
+
+    /// Draw the grid as text, one line per row, with `cell` giving the text
+    /// for each cell.
+    pub fn render<F, D>(&self, mut cell: F) -> String
+    where
+        F: FnMut(&T) -> D,
+        D: fmt::Display,
+    {
+        let mut text = String::new();
+        for row in self.rows() {
+            for c in row {
+                text.push_str(&cell(c).to_string());
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    /// Panics if `(x, y)` is outside the grid, rather than wrapping onto
+    /// another row.
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        let (width, height) = (self.width, self.height);
+        self.get(x, y).unwrap_or_else(|| {
+            panic!("({}, {}) is outside the {}x{} grid", x, y, width, height)
+        })
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(x, y).unwrap_or_else(|| {
+            panic!("({}, {}) is outside the {}x{} grid", x, y, width, height)
+        })
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows() {
+            for c in row {
+                write!(f, "{}", c)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn numbered() -> Grid<usize> {
+        let mut grid = Grid::new(3, 2, 0);
+        for y in 0..2 {
+            for x in 0..3 {
+                grid[(x, y)] = y * 3 + x;
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn bounds_checked() {
+        let grid = numbered();
+        assert_eq!(Some(&5), grid.get(2, 1));
+        assert_eq!(None, grid.get(3, 0));
+        assert_eq!(None, grid.get(0, 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "(3, 0) is outside the 3x2 grid")]
+    fn index_does_not_wrap() {
+        let _ = numbered()[(3, 0)];
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = numbered();
+        let rows: Vec<_> = grid.rows().collect();
+        assert_eq!(vec![&[0, 1, 2][..], &[3, 4, 5][..]], rows);
+        let columns: Vec<Vec<_>> = grid.columns().map(|c| c.cloned().collect()).collect();
+        assert_eq!(vec![vec![0, 3], vec![1, 4], vec![2, 5]], columns);
+        assert_eq!(0, grid.column(3).count());
+        assert_eq!(None, grid.row(2));
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = numbered();
+        let corner: Vec<_> = grid.neighbours4(0, 0).collect();
+        assert_eq!(vec![(1, 0), (0, 1)], corner);
+        let middle: Vec<_> = grid.neighbours8(1, 0).collect();
+        assert_eq!(vec![(0, 0), (2, 0), (0, 1), (1, 1), (2, 1)], middle);
+    }
+
+    #[test]
+    fn resize_keeps_cells() {
+        let mut grid = numbered();
+        grid.resize(2, 3, 9);
+        assert_eq!("01\n34\n99\n", grid.to_string());
+    }
+
+    #[test]
+    fn render_cells() {
+        let grid = numbered();
+        assert_eq!("..#\n###\n", grid.render(|&n| if n < 2 { '.' } else { '#' }));
+    }
+}
@@ -0,0 +1,252 @@
+//! Locating, reading and parsing puzzle input.
+//!
+//! The parsing helpers take lines as any `Deref<Target = str>` so days can be
+//! called with `&[String]` from a file or `&[&str]` in tests, and every error
+//! they return cites the 1-based line it came from.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use error::Error;
+
+/// Directory puzzle inputs are read from, relative to the current working
+/// directory rather than wherever the binary lives.
+pub const INPUT_DIR: &str = "input";
+
+/// The conventional location of a day's input, e.g. `input/day01.txt`.
+pub fn default_path(day: u32) -> PathBuf {
+    Path::new(INPUT_DIR).join(format!("day{:02}.txt", day))
+}
+
+/// Read every line of the file at `path`.
+///
+/// The error names the file so a missing input is easy to track down.
+pub fn read_file<P: AsRef<Path>>(path: P) -> Result<Vec<String>, Error> {
+    let path = path.as_ref();
+    let name = path.display().to_string();
+    let file = File::open(path).map_err(|e| Error::input(Some(&name), e))?;
+    read_lines(file).map_err(|e| Error::input(Some(&name), e))
+}
+
+/// Read every line from `r`.
+pub fn read<R: Read>(r: R) -> Result<Vec<String>, Error> {
+    read_lines(r).map_err(|e| Error::input(None, e))
+}
+
+fn read_lines<R: Read>(r: R) -> io::Result<Vec<String>> {
+    BufReader::new(r).lines().collect()
+}
+
+/// Parse every line into a `T`, describing what a bad line should have
+/// looked like with `expected`.
+pub fn parse_lines<T: FromStr, S: Deref<Target = str>>(
+    lines: &[S],
+    expected: &str,
+) -> Result<Vec<T>, Error> {
+    parse_lines_with(lines, |l| l.parse().map_err(|_| Error::parse(l, expected)))
+}
+
+/// Parse every line with `parse`, adding the line number to any error.
+pub fn parse_lines_with<T, S, F>(lines: &[S], parse: F) -> Result<Vec<T>, Error>
+where
+    S: Deref<Target = str>,
+    F: FnMut(&str) -> Result<T, Error>,
+{
+    parse_from(1, lines, parse)
+}
+
+/// Check every line with `parse`, returning an error for each bad line
+/// rather than stopping at the first.
+pub fn check_lines<T: FromStr, S: Deref<Target = str>>(lines: &[S], expected: &str) -> Vec<Error> {
+    check_lines_with(lines, |l| l.parse::<T>().map_err(|_| Error::parse(l, expected)))
+}
+
+/// Check every line with `parse`, see `check_lines`.
+pub fn check_lines_with<T, S, F>(lines: &[S], mut parse: F) -> Vec<Error>
+where
+    S: Deref<Target = str>,
+    F: FnMut(&str) -> Result<T, Error>,
+{
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, l)| parse(l).err().map(|e| e.at_line(i + 1)))
+        .collect()
+}
+
+fn parse_from<T, S, F>(first: usize, lines: &[S], mut parse: F) -> Result<Vec<T>, Error>
+where
+    S: Deref<Target = str>,
+    F: FnMut(&str) -> Result<T, Error>,
+{
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, l)| parse(l).map_err(|e| e.at_line(first + i)))
+        .collect()
+}
+
+/// The single line of input for days whose input is one long line, with
+/// surrounding whitespace trimmed.
+///
+/// Blank lines after it are allowed, anything else is an error.
+pub fn single_line<'a, S: Deref<Target = str>>(lines: &'a [S], expected: &str) -> Result<&'a str, Error> {
+    match check_single_line(lines, expected).into_iter().next() {
+        Some(err) => Err(err),
+        None => Ok(lines[0].trim()),
+    }
+}
+
+/// Check the input is a single line, see `single_line`, returning an error
+/// for a blank first line and for every line with something after it.
+pub fn check_single_line<S: Deref<Target = str>>(lines: &[S], expected: &str) -> Vec<Error> {
+    let mut errors = Vec::new();
+    let line = lines.first().map_or("", |l| l.trim());
+    if line.is_empty() {
+        errors.push(Error::parse(line, expected).at_line(1));
+    }
+    for (i, l) in lines.iter().enumerate().skip(1) {
+        if !l.trim().is_empty() {
+            errors.push(Error::parse(&**l, "nothing after the first line").at_line(i + 1));
+        }
+    }
+
+    errors
+}
+
+/// A run of lines separated from the rest of the input by blank lines.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Section<'a, S: 'a> {
+    /// The 1-based line number of the section's first line.
+    pub first: usize,
+    pub lines: &'a [S],
+}
+
+impl<'a, S: Deref<Target = str>> Section<'a, S> {
+    /// Parse every line of the section, see `parse_lines`.
+    pub fn parse_lines<T: FromStr>(&self, expected: &str) -> Result<Vec<T>, Error> {
+        self.parse_lines_with(|l| l.parse().map_err(|_| Error::parse(l, expected)))
+    }
+
+    /// Parse every line of the section with `parse`, adding the line number
+    /// within the whole input to any error.
+    pub fn parse_lines_with<T, F>(&self, parse: F) -> Result<Vec<T>, Error>
+    where
+        F: FnMut(&str) -> Result<T, Error>,
+    {
+        parse_from(self.first, self.lines, parse)
+    }
+}
+
+/// Split the input into sections on blank lines.
+///
+/// Any number of blank lines separate two sections, and leading or trailing
+/// blank lines don't produce empty ones.
+pub fn sections<S: Deref<Target = str>>(lines: &[S]) -> Vec<Section<'_, S>> {
+    let mut sections = Vec::new();
+    let mut start = None;
+    for (i, l) in lines.iter().enumerate() {
+        match (start, l.trim().is_empty()) {
+            (None, false) => start = Some(i),
+            (Some(s), true) => {
+                sections.push(Section {
+                    first: s + 1,
+                    lines: &lines[s..i],
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        sections.push(Section {
+            first: s + 1,
+            lines: &lines[s..],
+        });
+    }
+
+    sections
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn default_path_is_zero_padded() {
+        assert_eq!(Path::new("input/day07.txt"), default_path(7));
+        assert_eq!(Path::new("input/day25.txt"), default_path(25));
+    }
+
+    #[test]
+    fn missing_file_is_named() {
+        let err = read_file("input/day99.txt").unwrap_err().to_string();
+        assert!(err.starts_with("could not read input/day99.txt: "), "{}", err);
+    }
+
+    #[test]
+    fn read_lines() {
+        let lines = read("+1\n-2\n".as_bytes()).unwrap();
+        assert_eq!(vec!["+1", "-2"], lines);
+    }
+
+    #[test]
+    fn parse_every_line() {
+        assert_eq!(Ok(vec![1, -2]), parse_lines::<i32, _>(&["1", "-2"], "a number"));
+        assert_eq!(
+            Err(Error::parse("x", "a number").at_line(2)),
+            parse_lines::<i32, _>(&["1", "x"], "a number")
+        );
+    }
+
+    #[test]
+    fn check_every_line() {
+        assert_eq!(
+            vec![
+                Error::parse("x", "a number").at_line(2),
+                Error::parse("", "a number").at_line(4),
+            ],
+            check_lines::<i32, _>(&["1", "x", "3", ""], "a number")
+        );
+        assert!(check_lines::<i32, _>(&["1"], "a number").is_empty());
+    }
+
+    #[test]
+    fn single_trimmed_line() {
+        assert_eq!(Ok("abc"), single_line(&[" abc ", ""], "letters"));
+        assert_eq!(Err(Error::parse("", "letters").at_line(1)), single_line::<&str>(&[], "letters"));
+        assert_eq!(
+            Err(Error::parse("def", "nothing after the first line").at_line(3)),
+            single_line(&["abc", "", "def"], "letters")
+        );
+    }
+
+    #[test]
+    fn check_single_trimmed_line() {
+        assert!(check_single_line(&["abc", " "], "letters").is_empty());
+        assert_eq!(
+            vec![
+                Error::parse("", "letters").at_line(1),
+                Error::parse("def", "nothing after the first line").at_line(2),
+                Error::parse("ghi", "nothing after the first line").at_line(4),
+            ],
+            check_single_line(&["", "def", "", "ghi"], "letters")
+        );
+    }
+
+    #[test]
+    fn blank_line_sections() {
+        let lines = ["", "1", "2", "", "", "3", "x", ""];
+        let sections = sections(&lines);
+        assert_eq!(2, sections.len());
+        assert_eq!(2, sections[0].first);
+        assert_eq!(Ok(vec![1, 2]), sections[0].parse_lines::<i32>("a number"));
+        assert_eq!(
+            Err(Error::parse("x", "a number").at_line(7)),
+            sections[1].parse_lines::<i32>("a number")
+        );
+    }
+}
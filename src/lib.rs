@@ -0,0 +1,28 @@
+//! Solutions to the 2018 Advent of Code, along with the tooling to run,
+//! check and time them.
+//!
+//! Every day is registered in `solutions::all` and can be run through the
+//! object safe `solutions::Solution` trait, while each day module also
+//! exposes its parsing and solving functions directly.
+
+#[macro_use]
+extern crate log;
+extern crate regex;
+extern crate simplelog;
+
+pub mod answers;
+pub mod bench;
+pub mod error;
+pub mod generate;
+pub mod graph;
+pub mod grid;
+pub mod input;
+pub mod logging;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod solutions;
+pub mod watch;
+
+pub use error::Error;
+pub use solutions::{Answer, Part, Puzzle, Solution};
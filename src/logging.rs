@@ -0,0 +1,185 @@
+//! Logger setup for the command line, with per-module levels.
+//!
+//! simplelog only has a single level per logger, so every logger is wrapped
+//! in a `Filtered` that checks each record's target against `Filters` first.
+
+use std::fs::File;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use log::{Log, Metadata, Record};
+use simplelog::{CombinedLogger, Config, LevelFilter, SharedLogger, WriteLogger};
+
+/// The level to log at overall, and for particular modules.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filters {
+    default: LevelFilter,
+    modules: Vec<(Vec<String>, LevelFilter)>,
+}
+
+impl Filters {
+    pub fn new(default: LevelFilter) -> Self {
+        Filters {
+            default,
+            modules: Vec::new(),
+        }
+    }
+
+    /// The level for `-q`, nothing and each extra `-v`.
+    pub fn verbosity(quiet: bool, verbose: u64) -> LevelFilter {
+        match (quiet, verbose) {
+            (true, _) => LevelFilter::Warn,
+            (false, 0) => LevelFilter::Info,
+            (false, 1) => LevelFilter::Debug,
+            _ => LevelFilter::Trace,
+        }
+    }
+
+    /// Add a filter like `day03=debug`, which sets the level for any module
+    /// whose path contains `day03`.
+    pub fn add(&mut self, spec: &str) -> Result<(), String> {
+        let mut split = spec.splitn(2, '=');
+        let module = split.next().unwrap_or("").trim();
+        let level = split
+            .next()
+            .ok_or_else(|| format!("expected a filter like day03=debug, got {:?}", spec))?;
+        if module.is_empty() || module.split("::").any(str::is_empty) {
+            return Err(format!("expected a module path before '=', got {:?}", spec));
+        }
+        let level = LevelFilter::from_str(level.trim())
+            .map_err(|_| format!("unknown log level {:?} in {:?}", level, spec))?;
+        let path = module.split("::").map(str::to_string).collect();
+        self.modules.push((path, level));
+        Ok(())
+    }
+
+    /// The level for records from `target`.
+    ///
+    /// The filter matching the most specific part of the path wins, so
+    /// `day03` beats `aoc_2018::solutions` for `aoc_2018::solutions::day03`.
+    /// Later filters win ties.
+    pub fn level(&self, target: &str) -> LevelFilter {
+        let target: Vec<_> = target.split("::").collect();
+        let mut best = ((0, 0), self.default);
+        for (path, level) in &self.modules {
+            let end = target
+                .windows(path.len())
+                .rposition(|window| window.iter().zip(path).all(|(a, b)| a == b))
+                .map(|start| start + path.len());
+            if let Some(end) = end {
+                if (end, path.len()) >= best.0 {
+                    best = ((end, path.len()), *level);
+                }
+            }
+        }
+        best.1
+    }
+
+    /// The most verbose level anything may be logged at.
+    pub fn max(&self) -> LevelFilter {
+        self.modules
+            .iter()
+            .map(|&(_, level)| level)
+            .fold(self.default, ::std::cmp::max)
+    }
+}
+
+/// Wraps a logger so it only sees records `Filters` lets through.
+pub struct Filtered {
+    filters: Filters,
+    inner: Box<dyn SharedLogger>,
+}
+
+impl Filtered {
+    pub fn new(filters: Filters, inner: Box<dyn SharedLogger>) -> Box<Self> {
+        Box::new(Filtered { filters, inner })
+    }
+}
+
+impl Log for Filtered {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.filters.level(metadata.target()) && self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            self.inner.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+impl SharedLogger for Filtered {
+    fn level(&self) -> LevelFilter {
+        self.filters.max()
+    }
+
+    fn config(&self) -> Option<&Config> {
+        self.inner.config()
+    }
+
+    fn as_log(self: Box<Self>) -> Box<dyn Log> {
+        self
+    }
+}
+
+/// Log to stderr, and to `log_file` if given, as `filters` allow.
+///
+/// Everything goes to stderr so results on stdout, such as `--format json`,
+/// can be piped into other tools. simplelog's `TermLogger` would send
+/// anything below an error to stdout.
+pub fn init(filters: &Filters, log_file: Option<&Path>) -> Result<(), String> {
+    let max = filters.max();
+    let stderr = WriteLogger::new(max, Config::default(), io::stderr());
+    let mut loggers: Vec<Box<dyn SharedLogger>> = vec![Filtered::new(filters.clone(), stderr)];
+
+    if let Some(path) = log_file {
+        let file = File::create(path)
+            .map_err(|e| format!("could not create log file {}: {}", path.display(), e))?;
+        let writer = WriteLogger::new(max, Config::default(), file);
+        loggers.push(Filtered::new(filters.clone(), writer));
+    }
+
+    CombinedLogger::init(loggers).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn verbosity() {
+        assert_eq!(LevelFilter::Info, Filters::verbosity(false, 0));
+        assert_eq!(LevelFilter::Debug, Filters::verbosity(false, 1));
+        assert_eq!(LevelFilter::Trace, Filters::verbosity(false, 2));
+        assert_eq!(LevelFilter::Warn, Filters::verbosity(true, 0));
+    }
+
+    #[test]
+    fn module_filters() {
+        let mut filters = Filters::new(LevelFilter::Info);
+        filters.add("day03=debug").unwrap();
+        filters.add("aoc_2018::solutions=warn").unwrap();
+        filters.add("aoc_2018::solutions::day05=trace").unwrap();
+
+        assert_eq!(LevelFilter::Debug, filters.level("aoc_2018::solutions::day03"));
+        assert_eq!(LevelFilter::Warn, filters.level("aoc_2018::solutions::day01"));
+        assert_eq!(LevelFilter::Trace, filters.level("aoc_2018::solutions::day05"));
+        assert_eq!(LevelFilter::Info, filters.level("aoc_2018::runner"));
+        assert_eq!(LevelFilter::Info, filters.level("aoc_2018::solutions_extra"));
+        assert_eq!(LevelFilter::Trace, filters.max());
+    }
+
+    #[test]
+    fn bad_filters() {
+        let mut filters = Filters::new(LevelFilter::Info);
+        assert!(filters.add("day03").is_err());
+        assert!(filters.add("=debug").is_err());
+        assert!(filters.add("day03=loud").is_err());
+        assert_eq!(LevelFilter::Info, filters.max());
+    }
+}
@@ -1,57 +1,547 @@
+extern crate aoc_2018;
 extern crate clap;
 #[macro_use]
 extern crate log;
-extern crate regex;
-extern crate simplelog;
 
-use std::io;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::process;
 
-use clap::{App, Arg};
-use simplelog::{CombinedLogger, Config, LevelFilter, TermLogger};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
 
-mod solutions;
+use aoc_2018::answers::{self, Answers};
+use aoc_2018::logging::{self, Filters};
+use aoc_2018::report::{self, Format};
+use aoc_2018::solutions::day01::{self, History};
+use aoc_2018::solutions::{self, DisplayPart, Parts, Solution};
+use aoc_2018::watch::Watcher;
+use aoc_2018::{bench, generate, input, runner, scaffold};
 
 fn main() {
     let matches = App::new("AoC 2018")
+        .setting(AppSettings::SubcommandsNegateReqs)
         .arg(
-            Arg::with_name("debug")
-                .short("debug")
-                .help("Enables additional logging"),
+            Arg::with_name("verbose")
+                .short("v")
+                .long("verbose")
+                .multiple(true)
+                .global(true)
+                .help("Log more, -v for debug and -vv for trace output"),
+        ).arg(
+            Arg::with_name("quiet")
+                .short("q")
+                .long("quiet")
+                .conflicts_with("verbose")
+                .global(true)
+                .help("Only log warnings and errors"),
+        ).arg(
+            Arg::with_name("log")
+                .long("log")
+                .takes_value(true)
+                .value_name("MODULE=LEVEL")
+                .multiple(true)
+                .number_of_values(1)
+                .global(true)
+                .help("Set the level for one module, e.g. --log day03=debug"),
+        ).arg(
+            Arg::with_name("log_file")
+                .long("log-file")
+                .takes_value(true)
+                .value_name("PATH")
+                .global(true)
+                .help("Also write log output to PATH"),
         ).arg(
             Arg::with_name("DAY")
                 .required(true)
                 .index(1)
                 .help("Which day to run"),
+        ).arg(input_arg())
+        .arg(part_arg())
+        .arg(
+            Arg::with_name("watch")
+                .long("watch")
+                .help("Re-run the day whenever its input file changes"),
+        ).arg(
+            Arg::with_name("examples")
+                .long("examples")
+                .takes_value(true)
+                .value_name("PATH")
+                .requires("watch")
+                .help("Also watch and run the day on the examples at PATH"),
+        ).arg(
+            Arg::with_name("format")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["text", "json"])
+                .default_value("text")
+                .global(true)
+                .help("How to print results"),
+        ).subcommand(
+            SubCommand::with_name("all")
+                .about("Run every implemented day against input/dayNN.txt"),
+        ).subcommand(
+            SubCommand::with_name("bench")
+                .about("Time parsing and each part of a day over several runs")
+                .arg(
+                    Arg::with_name("DAY")
+                        .required(true)
+                        .help("Which day to benchmark"),
+                ).arg(input_arg())
+                .arg(part_arg())
+                .arg(
+                    Arg::with_name("runs")
+                        .short("n")
+                        .long("runs")
+                        .takes_value(true)
+                        .default_value("10")
+                        .help("How many times to run the day"),
+                ),
+        ).subcommand(
+            SubCommand::with_name("new")
+                .about("Create src/solutions/dayNN.rs from template.rs and register it")
+                .arg(
+                    Arg::with_name("DAY")
+                        .required(true)
+                        .help("Which day to create"),
+                ).arg(
+                    Arg::with_name("title")
+                        .long("title")
+                        .takes_value(true)
+                        .default_value("TODO")
+                        .help("The puzzle's title"),
+                ),
+        ).subcommand(
+            SubCommand::with_name("check")
+                .about("Check input files are valid for their day without solving them")
+                .arg(days_arg())
+                .arg(input_arg()),
+        ).subcommand(
+            SubCommand::with_name("generate")
+                .about("Print a random input for a day, for stress testing")
+                .arg(
+                    Arg::with_name("DAY")
+                        .required(true)
+                        .help("Which day to generate input for"),
+                ).arg(
+                    Arg::with_name("size")
+                        .long("size")
+                        .takes_value(true)
+                        .value_name("N")
+                        .help("Lines, or units for day 5, defaults to about the real input's size"),
+                ).arg(
+                    Arg::with_name("seed")
+                        .long("seed")
+                        .takes_value(true)
+                        .default_value("0")
+                        .help("The same seed always gives the same input"),
+                ).arg(
+                    Arg::with_name("output")
+                        .short("o")
+                        .long("output")
+                        .takes_value(true)
+                        .value_name("PATH")
+                        .help("Write the input to PATH instead of stdout"),
+                ),
+        ).subcommand(
+            SubCommand::with_name("history")
+                .about("Show how day 1's frequency changes until it first repeats")
+                .arg(input_arg())
+                .arg(
+                    Arg::with_name("trace")
+                        .long("trace")
+                        .help("Print the frequency after every change"),
+                ).arg(
+                    Arg::with_name("csv")
+                        .long("csv")
+                        .takes_value(true)
+                        .value_name("PATH")
+                        .help("Write the frequency after every change to PATH as CSV"),
+                ),
+        ).subcommand(
+            SubCommand::with_name("verify")
+                .about("Check answers against those recorded in the answers file")
+                .arg(days_arg())
+                .arg(answers_arg()),
+        ).subcommand(
+            SubCommand::with_name("record")
+                .about("Record the current answers into the answers file")
+                .arg(days_arg())
+                .arg(answers_arg()),
         ).get_matches();
 
-    let debug = matches.is_present("debug");
-    let day = matches.value_of("DAY").unwrap().parse();
-
-    CombinedLogger::init(vec![
-        TermLogger::new(
-            if debug {
-                LevelFilter::Debug
-            } else {
-                LevelFilter::Info
-            },
-            Config::default(),
-        ).unwrap(),
-    ]).unwrap();
-
-    if let Err(_) = day {
-        error!("day must be supplied as a single integer");
-        return;
-    }
-    let day = day.unwrap();
+    if let Err(err) = init_logging(&matches) {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+
+    let format: Format = matches.value_of("format").unwrap().parse().unwrap();
+
+    let result = match matches.subcommand() {
+        ("all", _) => {
+            print!("{}", report::runs(&runner::run_all(), format));
+            Ok(())
+        }
+        ("bench", Some(sub)) => run_bench(sub, format),
+        ("new", Some(sub)) => new_day(sub),
+        ("check", Some(sub)) => check(sub),
+        ("generate", Some(sub)) => generate_input(sub),
+        ("history", Some(sub)) => frequency_history(sub),
+        ("verify", Some(sub)) => verify(sub),
+        ("record", Some(sub)) => record(sub),
+        _ => run_day(&matches, format),
+    };
+
+    if let Err(err) = result {
+        error!("{}", err);
+        process::exit(1);
+    }
+}
+
+fn init_logging(matches: &ArgMatches) -> Result<(), String> {
+    let level = Filters::verbosity(
+        matches.is_present("quiet"),
+        matches.occurrences_of("verbose"),
+    );
+    let mut filters = Filters::new(level);
+    for spec in matches.values_of("log").into_iter().flatten() {
+        filters.add(spec)?;
+    }
+    logging::init(&filters, matches.value_of("log_file").map(Path::new))
+}
+
+fn input_arg() -> Arg<'static, 'static> {
+    Arg::with_name("input")
+        .short("i")
+        .long("input")
+        .takes_value(true)
+        .value_name("PATH")
+        .help("Read puzzle input from PATH instead of stdin or input/dayNN.txt")
+}
+
+fn part_arg() -> Arg<'static, 'static> {
+    Arg::with_name("part")
+        .short("p")
+        .long("part")
+        .takes_value(true)
+        .possible_values(&["1", "2", "both"])
+        .default_value("both")
+        .help("Which part to solve")
+}
+
+/// The parts selected with `--part`.
+fn selected_parts(matches: &ArgMatches) -> Parts {
+    // clap has already checked it's one of the possible values
+    matches.value_of("part").unwrap().parse().unwrap()
+}
+
+fn days_arg() -> Arg<'static, 'static> {
+    Arg::with_name("DAY")
+        .multiple(true)
+        .help("Days to include, defaults to every implemented day")
+}
+
+fn answers_arg() -> Arg<'static, 'static> {
+    Arg::with_name("answers")
+        .long("answers")
+        .takes_value(true)
+        .value_name("PATH")
+        .default_value(answers::ANSWERS_FILE)
+        .help("The answers file to use")
+}
+
+/// Run a single day, exiting with a failure status if it has any errors.
+fn run_day(matches: &ArgMatches, format: Format) -> Result<(), String> {
+    let day = parse_day(matches.value_of("DAY").unwrap())?;
+
+    let solution = solutions::get(day).ok_or_else(|| {
+        let known: Vec<_> = solutions::all().iter().map(|s| s.day().to_string()).collect();
+        format!(
+            "Day {} not yet implemented, available days: {}",
+            day,
+            known.join(", ")
+        )
+    })?;
+
+    let parts = selected_parts(matches);
+    if matches.is_present("watch") {
+        let path = matches
+            .value_of("input")
+            .map_or_else(|| input::default_path(day), PathBuf::from);
+        let examples = matches.value_of("examples").map(Path::new);
+        watch_day(solution, &path, examples, parts, format);
+    }
+
+    // an explicit path wins, then piped stdin, then the input/ directory
     let stdin = io::stdin();
+    let lines = match matches.value_of("input") {
+        Some(path) => input::read_file(path),
+        None if stdin.is_terminal() => input::read_file(input::default_path(day)),
+        None => input::read(stdin.lock()),
+    };
+    let lines = lines.map_err(|e| e.to_string())?;
+
+    let run = runner::run(solution, &lines, parts);
+    print!("{}", report::run(&run, format));
+    if run.failed() {
+        process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Run `solution` on the input at `path`, and on `examples` if given, then
+/// again every time either file changes.
+fn watch_day(
+    solution: &dyn Solution,
+    path: &Path,
+    examples: Option<&Path>,
+    parts: Parts,
+    format: Format,
+) -> ! {
+    let mut paths = vec![path];
+    paths.extend(examples);
+    let mut watcher = Watcher::new(&paths);
+
+    loop {
+        for path in &paths {
+            let lines = match input::read_file(path) {
+                Ok(lines) => lines,
+                Err(err) => {
+                    error!("{}", err);
+                    continue;
+                }
+            };
+
+            let run = runner::run(solution, &lines, parts);
+            match format {
+                Format::Text => print!(
+                    "== {} ==\n{}Time: {}\n\n",
+                    path.display(),
+                    report::text(&run),
+                    report::format_duration(run.timings.total())
+                ),
+                Format::Json => print!("{}", report::run(&run, format)),
+            }
+        }
+
+        info!("Watching for changes, press Ctrl-C to stop");
+        for changed in watcher.wait() {
+            info!("{} changed", changed.display());
+        }
+    }
+}
+
+/// Parse a day number given on the command line.
+fn parse_day(day: &str) -> Result<u32, String> {
+    day.parse()
+        .map_err(|_| format!("day must be a single integer, got {:?}", day))
+}
+
+/// The solutions named by the DAY arguments, or all of them if none are given.
+fn selected_days(matches: &ArgMatches) -> Result<Vec<&'static dyn Solution>, String> {
+    let days = match matches.values_of("DAY") {
+        Some(days) => days,
+        None => return Ok(solutions::all()),
+    };
 
-    match day {
-        1 => solutions::day01::work(stdin.lock()),
-        2 => solutions::day02::work(stdin.lock()),
-        3 => solutions::day03::work(stdin.lock()),
-        4 => solutions::day04::work(stdin.lock()),
-        5 => solutions::day05::work(stdin.lock()),
-        7 => solutions::day07::work(stdin.lock()),
-        _ => error!("Day {} not yet implemented", day),
+    days.map(|d| {
+        let day = parse_day(d)?;
+        solutions::get(day).ok_or_else(|| format!("Day {} not yet implemented", day))
+    }).collect()
+}
+
+/// Benchmark a single day against its input.
+fn run_bench(matches: &ArgMatches, format: Format) -> Result<(), String> {
+    let solution = match selected_days(matches)?.pop() {
+        Some(solution) => solution,
+        None => return Err("no day to benchmark".to_string()),
+    };
+    let runs = matches
+        .value_of("runs")
+        .unwrap()
+        .parse()
+        .ok()
+        .filter(|&n| n > 0)
+        .ok_or("runs must be a positive integer")?;
+
+    let lines = match matches.value_of("input") {
+        Some(path) => input::read_file(path).map_err(|e| e.to_string())?,
+        None => input::read_file(input::default_path(solution.day())).map_err(|e| e.to_string())?,
+    };
+
+    let bench = bench::bench(solution, &lines, runs, selected_parts(matches));
+    match format {
+        Format::Text => {
+            print!("{}", bench::table(&bench));
+            if bench.parts.includes(1) {
+                println!("Part 1: {}", DisplayPart(&bench.answer.part1));
+            }
+            if bench.parts.includes(2) {
+                println!("Part 2: {}", DisplayPart(&bench.answer.part2));
+            }
+        }
+        Format::Json => println!("{}", bench::json(&bench)),
+    }
+
+    Ok(())
+}
+
+/// Scaffold a new day in the current directory.
+fn new_day(matches: &ArgMatches) -> Result<(), String> {
+    let day = parse_day(matches.value_of("DAY").unwrap())?;
+
+    let paths = scaffold::new_day(Path::new("."), day, matches.value_of("title").unwrap())?;
+    for path in paths {
+        println!("Created {}", path.display());
+    }
+
+    Ok(())
+}
+
+/// Report every problem in each selected day's input, without solving.
+fn check(matches: &ArgMatches) -> Result<(), String> {
+    let days = selected_days(matches)?;
+    let input = matches.value_of("input");
+    if input.is_some() && days.len() != 1 {
+        return Err("--input can only be checked against a single day".to_string());
+    }
+
+    let mut problems = 0;
+    for solution in days {
+        let path = input.map_or_else(|| input::default_path(solution.day()), PathBuf::from);
+        let errors = match input::read_file(&path) {
+            Ok(lines) => solution.check(&lines),
+            Err(err) => {
+                println!("Day {}: {}", solution.day(), err);
+                problems += 1;
+                continue;
+            }
+        };
+
+        if errors.is_empty() {
+            println!("Day {}: {} is valid", solution.day(), path.display());
+        }
+        for err in &errors {
+            println!("{}", err);
+        }
+        problems += errors.len();
+    }
+
+    if problems == 0 {
+        Ok(())
+    } else {
+        Err(format!("found {} problems", problems))
+    }
+}
+
+fn generate_input(matches: &ArgMatches) -> Result<(), String> {
+    let day = parse_day(matches.value_of("DAY").unwrap())?;
+    let size = match matches.value_of("size") {
+        Some(size) => size
+            .parse()
+            .map_err(|_| format!("size must be a positive integer, got {:?}", size))?,
+        None => generate::default_size(day)
+            .ok_or_else(|| format!("no input generator for day {}", day))?,
+    };
+    let seed = matches.value_of("seed").unwrap();
+    let seed = seed
+        .parse()
+        .map_err(|_| format!("seed must be a non-negative integer, got {:?}", seed))?;
+
+    let mut text = generate::generate(day, size, seed)?.join("\n");
+    text.push('\n');
+    match matches.value_of("output") {
+        Some(path) => {
+            fs::write(path, text).map_err(|e| format!("could not write {}: {}", path, e))?;
+            info!("Wrote day {} input to {}", day, path);
+        }
+        None => print!("{}", text),
     }
+
+    Ok(())
+}
+
+fn frequency_history(matches: &ArgMatches) -> Result<(), String> {
+    let path = matches
+        .value_of("input")
+        .map_or_else(|| input::default_path(1), PathBuf::from);
+    let lines = input::read_file(&path).map_err(|e| e.to_string())?;
+    let changes = day01::parse_changes(&lines).map_err(|e| e.in_day(1).to_string())?;
+    let history = History::new(&changes);
+
+    print!("{}", history);
+    if matches.is_present("trace") {
+        println!("Iteration  Pass  Change  Frequency");
+        println!("{:>9}  {:>4}  {:>6}  {:>9}", 0, 1, "", 0);
+        for (i, (change, frequency)) in history.trace().enumerate() {
+            println!(
+                "{:>9}  {:>4}  {:>+6}  {:>9}",
+                i + 1,
+                history.pass(i + 1),
+                change,
+                frequency
+            );
+        }
+    }
+    if let Some(csv) = matches.value_of("csv") {
+        let write_error = |e| format!("could not write {}: {}", csv, e);
+        let file = File::create(csv).map_err(write_error)?;
+        history.write_csv(BufWriter::new(file)).map_err(write_error)?;
+        info!("Wrote {} iterations to {}", history.iterations() + 1, csv);
+    }
+
+    Ok(())
+}
+
+/// Run the selected days and compare them against the answers file.
+fn verify(matches: &ArgMatches) -> Result<(), String> {
+    let path = matches.value_of("answers").unwrap();
+    let answers = Answers::load(path)?;
+    if answers.is_empty() {
+        return Err(format!("no answers recorded in {}", path));
+    }
+
+    let runs: Vec<_> = selected_days(matches)?
+        .into_iter()
+        .map(runner::run_default)
+        .collect();
+    let verification = answers.verify(&runs);
+
+    for mismatch in &verification.mismatches {
+        println!("{}", mismatch);
+    }
+    println!(
+        "{} answers matched, {} mismatched",
+        verification.matched,
+        verification.mismatches.len()
+    );
+
+    if verification.passed() {
+        Ok(())
+    } else {
+        Err("answers do not match those recorded".to_string())
+    }
+}
+
+/// Run the selected days and save their answers to the answers file.
+fn record(matches: &ArgMatches) -> Result<(), String> {
+    let path = matches.value_of("answers").unwrap();
+    let mut answers = Answers::load(path)?;
+
+    let runs: Vec<_> = selected_days(matches)?
+        .into_iter()
+        .map(runner::run_default)
+        .collect();
+    for run in &runs {
+        if let Err(ref err) = run.answer {
+            warn!("Day {} not recorded: {}", run.day, err);
+        }
+    }
+
+    let recorded = answers.record(&runs);
+    answers.save(path)?;
+    println!("Recorded {} answers in {}", recorded, path);
+
+    Ok(())
 }
@@ -0,0 +1,349 @@
+//! Formatting run results for people and other tools.
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use runner::Run;
+use solutions::{DisplayPart, Part, Parts};
+
+/// How results are written out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// Plain text for reading in a terminal.
+    Text,
+    /// One JSON object per day, each on its own line.
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown output format {:?}", s)),
+        }
+    }
+}
+
+/// Format runs one after another in the given format.
+pub fn runs(runs: &[Run], format: Format) -> String {
+    match format {
+        Format::Text => table(runs),
+        Format::Json => runs.iter().map(|r| json(r) + "\n").collect(),
+    }
+}
+
+/// Format a single run in the given format.
+pub fn run(run: &Run, format: Format) -> String {
+    match format {
+        Format::Text => text(run),
+        Format::Json => json(run) + "\n",
+    }
+}
+
+/// Describe a single run for a human reader, leaving out parts that weren't
+/// asked for.
+pub fn text(run: &Run) -> String {
+    let mut out = format!("Day {}: {}\n", run.day, run.title);
+    match run.answer {
+        Ok(ref answer) => {
+            if run.parts.includes(1) {
+                out.push_str(&format!("Part 1: {}\n", DisplayPart(&answer.part1)));
+            }
+            if run.parts.includes(2) {
+                out.push_str(&format!("Part 2: {}\n", DisplayPart(&answer.part2)));
+            }
+        }
+        Err(ref err) => out.push_str(&format!("Error: {}\n", err)),
+    }
+
+    out
+}
+
+/// Describe a single run as a JSON object.
+///
+/// Parts that weren't asked for are left out. The others are `null` when not
+/// implemented, otherwise an object holding either its `answer` or its
+/// `error`. Timings are in nanoseconds and `error` is set when the day could
+/// not be run at all.
+pub fn json(run: &Run) -> String {
+    let (part1, part2, error) = match run.answer {
+        Ok(ref answer) => (json_part(&answer.part1), json_part(&answer.part2), "null".to_string()),
+        Err(ref err) => ("null".to_string(), "null".to_string(), json_string(&err.to_string())),
+    };
+    let t = &run.timings;
+
+    format!(
+        concat!(
+            r#"{{"day":{},"title":{},{},"#,
+            r#""timings":{{"parse_ns":{},"part1_ns":{},"part2_ns":{},"total_ns":{}}},"#,
+            r#""error":{}}}"#
+        ),
+        run.day,
+        json_string(run.title),
+        json_parts(run.parts, &part1, &part2),
+        nanos(t.parse),
+        nanos(t.part1),
+        nanos(t.part2),
+        nanos(t.total()),
+        error
+    )
+}
+
+/// The `"part1"` and `"part2"` fields of an object, for the selected parts.
+pub fn json_parts(parts: Parts, part1: &str, part2: &str) -> String {
+    let mut fields = Vec::new();
+    if parts.includes(1) {
+        fields.push(format!(r#""part1":{}"#, part1));
+    }
+    if parts.includes(2) {
+        fields.push(format!(r#""part2":{}"#, part2));
+    }
+    fields.join(",")
+}
+
+pub fn json_part(part: &Part) -> String {
+    match *part {
+        Some(Ok(ref answer)) => format!(r#"{{"answer":{}}}"#, json_string(answer)),
+        Some(Err(ref err)) => format!(r#"{{"error":{}}}"#, json_string(&err.to_string())),
+        None => "null".to_string(),
+    }
+}
+
+/// Quote and escape `s` as a JSON string.
+pub fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+pub fn nanos(d: Duration) -> u64 {
+    d.as_secs() * 1_000_000_000 + u64::from(d.subsec_nanos())
+}
+
+/// Format a duration with a unit suited to its size, e.g. `1.52ms`.
+pub fn format_duration(d: Duration) -> String {
+    let nanos = d.as_secs() as f64 * 1e9 + f64::from(d.subsec_nanos());
+    if nanos >= 1e9 {
+        format!("{:.2}s", nanos / 1e9)
+    } else if nanos >= 1e6 {
+        format!("{:.2}ms", nanos / 1e6)
+    } else if nanos >= 1e3 {
+        format!("{:.2}µs", nanos / 1e3)
+    } else {
+        format!("{}ns", nanos)
+    }
+}
+
+/// Render runs as a table with a row per day.
+///
+/// Days that could not be run are marked in the table and their errors are
+/// listed underneath it.
+pub fn table(runs: &[Run]) -> String {
+    let mut rows = vec![[
+        "Day".to_string(),
+        "Title".to_string(),
+        "Part 1".to_string(),
+        "Part 2".to_string(),
+        "Time".to_string(),
+    ]];
+    let mut errors = Vec::new();
+    for run in runs {
+        let (part1, part2) = match run.answer {
+            Ok(ref answer) => (
+                DisplayPart(&answer.part1).to_string(),
+                DisplayPart(&answer.part2).to_string(),
+            ),
+            Err(ref err) => {
+                errors.push(format!("Day {}: {}", run.day, err));
+                ("error".to_string(), "error".to_string())
+            }
+        };
+        rows.push([
+            run.day.to_string(),
+            run.title.to_string(),
+            part1,
+            part2,
+            format_duration(run.timings.total()),
+        ]);
+    }
+
+    let mut widths = [0; 5];
+    for row in &rows {
+        for (w, cell) in widths.iter_mut().zip(row.iter()) {
+            *w = (*w).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    for row in &rows {
+        let line = format!(
+            "{:>w0$}  {:<w1$}  {:<w2$}  {:<w3$}  {:>w4$}",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            row[4],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3],
+            w4 = widths[4],
+        );
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    for err in &errors {
+        out.push_str(err);
+        out.push('\n');
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use error::Error;
+    use solutions::{solved, Answer, Timings};
+
+    #[test]
+    fn duration_units() {
+        assert_eq!("12ns", format_duration(Duration::new(0, 12)));
+        assert_eq!("1.50µs", format_duration(Duration::new(0, 1_500)));
+        assert_eq!("2.25ms", format_duration(Duration::new(0, 2_250_000)));
+        assert_eq!("3.00s", format_duration(Duration::new(3, 0)));
+    }
+
+    #[test]
+    fn text_selected_part() {
+        let run = Run {
+            day: 1,
+            title: "Chronal Calibration",
+            parts: Parts::Part2,
+            answer: Ok(Answer {
+                part1: None,
+                part2: solved(219),
+            }),
+            timings: Timings::default(),
+        };
+        assert_eq!("Day 1: Chronal Calibration\nPart 2: 219\n", text(&run));
+    }
+
+    #[test]
+    fn json_escapes_strings() {
+        assert_eq!(r#""plain""#, json_string("plain"));
+        assert_eq!(r#""a \"b\"\\c\nd\u0001""#, json_string("a \"b\"\\c\nd\u{1}"));
+    }
+
+    #[test]
+    fn json_run() {
+        let run = Run {
+            day: 3,
+            title: "No Matter How You Slice It",
+            parts: Parts::Both,
+            answer: Ok(Answer {
+                part1: solved(4),
+                part2: Some(Err(Error::no_answer("no lone claim"))),
+            }),
+            timings: Timings {
+                parse: Duration::new(0, 10),
+                part1: Duration::new(0, 20),
+                part2: Duration::new(1, 0),
+            },
+        };
+        let expected = concat!(
+            r#"{"day":3,"title":"No Matter How You Slice It","#,
+            r#""part1":{"answer":"4"},"part2":{"error":"no lone claim"},"#,
+            r#""timings":{"parse_ns":10,"part1_ns":20,"part2_ns":1000000000,"total_ns":1000000030},"#,
+            r#""error":null}"#
+        );
+        assert_eq!(expected, json(&run));
+    }
+
+    #[test]
+    fn json_failed_run() {
+        let run = Run {
+            day: 4,
+            title: "Repose Record",
+            parts: Parts::Both,
+            answer: Err(Error::input(Some("input/day04.txt"), "missing")),
+            timings: Timings::default(),
+        };
+        let expected = concat!(
+            r#"{"day":4,"title":"Repose Record","part1":null,"part2":null,"#,
+            r#""timings":{"parse_ns":0,"part1_ns":0,"part2_ns":0,"total_ns":0},"#,
+            r#""error":"could not read input/day04.txt: missing"}"#
+        );
+        assert_eq!(expected, json(&run));
+    }
+
+    #[test]
+    fn json_leaves_out_unselected_part() {
+        let run = Run {
+            day: 5,
+            title: "Alchemical Reduction",
+            parts: Parts::Part2,
+            answer: Ok(Answer {
+                part1: None,
+                part2: solved(4),
+            }),
+            timings: Timings::default(),
+        };
+        let expected = concat!(
+            r#"{"day":5,"title":"Alchemical Reduction","part2":{"answer":"4"},"#,
+            r#""timings":{"parse_ns":0,"part1_ns":0,"part2_ns":0,"total_ns":0},"#,
+            r#""error":null}"#
+        );
+        assert_eq!(expected, json(&run));
+    }
+
+    #[test]
+    fn table_layout() {
+        let runs = vec![
+            Run {
+                day: 1,
+                title: "Chronal Calibration",
+                parts: Parts::Both,
+                answer: Ok(Answer {
+                    part1: solved(445),
+                    part2: solved(219),
+                }),
+                timings: Timings {
+                    parse: Duration::new(0, 500),
+                    part1: Duration::new(0, 600),
+                    part2: Duration::new(0, 400),
+                },
+            },
+            Run {
+                day: 4,
+                title: "Repose Record",
+                parts: Parts::Both,
+                answer: Err(Error::no_answer("missing")),
+                timings: Timings::default(),
+            },
+        ];
+
+        let expected = "\
+Day  Title                Part 1  Part 2    Time
+  1  Chronal Calibration  445     219     1.50µs
+  4  Repose Record        error   error      0ns
+Day 4: missing
+";
+        assert_eq!(expected, table(&runs));
+    }
+}
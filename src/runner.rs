@@ -0,0 +1,131 @@
+//! Running solutions against their inputs and collecting the results.
+
+use std::panic::{self, AssertUnwindSafe};
+use error::Error;
+use input;
+use solutions::{self, Answer, Parts, Solution, Timings};
+
+/// The result of running one day.
+#[derive(Debug)]
+pub struct Run {
+    pub day: u32,
+    pub title: &'static str,
+    /// The parts that were asked for, the others are left unsolved.
+    pub parts: Parts,
+    /// The answers, or why the day could not be run at all.
+    pub answer: Result<Answer, Error>,
+    /// Time spent solving, excluding reading the input.
+    pub timings: Timings,
+}
+
+impl Run {
+    /// Whether the day couldn't be run or either part failed.
+    pub fn failed(&self) -> bool {
+        match self.answer {
+            Ok(ref answer) => [&answer.part1, &answer.part2]
+                .iter()
+                .any(|p| p.as_ref().is_some_and(|r| r.is_err())),
+            Err(_) => true,
+        }
+    }
+}
+
+/// Run the selected parts of `solution` against already loaded input.
+///
+/// A panicking solver is reported as a failed run rather than taking the
+/// caller down with it.
+pub fn run(solution: &dyn Solution, lines: &[String], parts: Parts) -> Run {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| solution.timed(lines, parts)));
+    let (answer, timings) = match result {
+        Ok((answer, timings)) => (Ok(answer), timings),
+        Err(payload) => (Err(payload), Timings::default()),
+    };
+
+    Run {
+        day: solution.day(),
+        title: solution.title(),
+        parts,
+        answer: answer.map_err(|payload| {
+            let err = if let Some(msg) = payload.downcast_ref::<&str>() {
+                Error::panic(*msg)
+            } else if let Some(msg) = payload.downcast_ref::<String>() {
+                Error::panic(msg.as_str())
+            } else {
+                Error::panic("unknown cause")
+            };
+            err.in_day(solution.day())
+        }),
+        timings,
+    }
+}
+
+/// Run `solution` against its input in the input directory.
+pub fn run_default(solution: &dyn Solution) -> Run {
+    match input::read_file(input::default_path(solution.day())) {
+        Ok(lines) => run(solution, &lines, Parts::Both),
+        Err(err) => Run {
+            day: solution.day(),
+            title: solution.title(),
+            parts: Parts::Both,
+            answer: Err(err),
+            timings: Timings::default(),
+        },
+    }
+}
+
+/// Run every registered day against its default input, in order.
+pub fn run_all() -> Vec<Run> {
+    solutions::all()
+        .into_iter()
+        .map(|s| {
+            info!("Running day {}", s.day());
+            run_default(s)
+        }).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solutions::{solved, Part};
+
+    struct Panics;
+
+    impl Solution for Panics {
+        fn day(&self) -> u32 {
+            99
+        }
+
+        fn title(&self) -> &'static str {
+            "Panics"
+        }
+
+        fn part1(&self, _lines: &[String]) -> Part {
+            panic!("bad input")
+        }
+    }
+
+    #[test]
+    fn run_catches_panics() {
+        let run = run(&Panics, &[], Parts::Both);
+        assert_eq!(99, run.day);
+        assert!(run.failed());
+        assert_eq!(Err(Error::panic("bad input").in_day(99)), run.answer);
+    }
+
+    #[test]
+    fn run_default_reports_missing_input() {
+        let run = run_default(&Panics);
+        assert_eq!(
+            "could not read input/day99.txt: No such file or directory (os error 2)",
+            run.answer.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn run_collects_answers() {
+        let lines = vec!["+1".to_string(), "-2".to_string(), "+3".to_string(), "+1".to_string()];
+        let run = run(&solutions::day01::Day01, &lines, Parts::Both);
+        assert!(!run.failed());
+        assert_eq!(solved(3), run.answer.unwrap().part1);
+    }
+}
@@ -0,0 +1,237 @@
+//! Generating the skeleton for a new day's solution.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The starting point for every day, see `template.rs`.
+const TEMPLATE: &str = include_str!("../template.rs");
+
+/// Directory holding the day modules, within the `root` given to `new_day`.
+const SOLUTIONS_DIR: &str = "src/solutions";
+
+/// Directory holding each day's example fixtures, also within `root`.
+const FIXTURES_DIR: &str = "tests/fixtures";
+
+/// The answers file left next to a new day's empty example. With no answers
+/// in it the examples test fails until the puzzle's example is filled in.
+const EXAMPLE_ANSWERS: &str = "\
+# Expected answers for the puzzle's example in example.txt, one per line as
+# `<day> <part> <answer>`, e.g. `DAY 1 42`.
+";
+
+/// Create `src/solutions/dayNN.rs` under `root` and register it in the
+/// solutions module, along with a stub example in `tests/fixtures/dayNN/`
+/// unless that directory already exists. Returns the paths of the new files.
+///
+/// Refuses to touch a day that already exists.
+pub fn new_day(root: &Path, day: u32, title: &str) -> Result<Vec<PathBuf>, String> {
+    if !(1..=25).contains(&day) {
+        return Err(format!("day must be between 1 and 25, got {}", day));
+    }
+
+    let dir = root.join(SOLUTIONS_DIR);
+    let path = dir.join(format!("{}.rs", module_name(day)));
+    if path.exists() {
+        return Err(format!("{} already exists", path.display()));
+    }
+
+    let mod_path = dir.join("mod.rs");
+    let mod_rs = fs::read_to_string(&mod_path)
+        .map_err(|e| format!("could not read {}: {}", mod_path.display(), e))?;
+    let mod_rs = register(&mod_rs, day)?;
+
+    fs::write(&path, render(day, title))
+        .map_err(|e| format!("could not write {}: {}", path.display(), e))?;
+    fs::write(&mod_path, mod_rs)
+        .map_err(|e| format!("could not write {}: {}", mod_path.display(), e))?;
+
+    let mut created = vec![path];
+    let fixtures = root.join(FIXTURES_DIR).join(module_name(day));
+    if !fixtures.exists() {
+        fs::create_dir_all(&fixtures)
+            .map_err(|e| format!("could not create {}: {}", fixtures.display(), e))?;
+        let answers = EXAMPLE_ANSWERS.replace("DAY", &day.to_string());
+        for &(name, contents) in &[("example.txt", ""), ("example.answers", &answers[..])] {
+            let path = fixtures.join(name);
+            fs::write(&path, contents)
+                .map_err(|e| format!("could not write {}: {}", path.display(), e))?;
+            created.push(path);
+        }
+    }
+
+    Ok(created)
+}
+
+fn module_name(day: u32) -> String {
+    format!("day{:02}", day)
+}
+
+fn struct_name(day: u32) -> String {
+    format!("Day{:02}", day)
+}
+
+/// Fill in the template for `day`.
+fn render(day: u32, title: &str) -> String {
+    TEMPLATE
+        .replace("DayNN", &struct_name(day))
+        .replace("const DAY: u32 = 0;", &format!("const DAY: u32 = {};", day))
+        .replace("\"TITLE\"", &format!("{:?}", title))
+}
+
+/// Add `day` to the module declarations and registry in `mod_rs`, keeping
+/// both in day order.
+fn register(mod_rs: &str, day: u32) -> Result<String, String> {
+    let module = module_name(day);
+    let decl = format!("pub mod {};", module);
+    let entry = format!("&{}::{},", module, struct_name(day));
+
+    let lines: Vec<&str> = mod_rs.lines().collect();
+    if lines.iter().any(|l| l.trim() == decl) {
+        return Err(format!("{} is already declared in the solutions module", module));
+    }
+
+    let decl_at = insert_point(&lines, |l| l.starts_with("pub mod day"), |l| l < decl.as_str())
+        .ok_or("could not find the day module declarations")?;
+    let is_entry = |l: &str| l.trim().starts_with("&day");
+    let entry_at = insert_point(&lines, is_entry, |l| l.trim() < entry.as_str())
+        .ok_or("could not find the solution registry")?;
+    let indent: String = lines
+        .iter()
+        .find(|l| is_entry(l))
+        .unwrap()
+        .chars()
+        .take_while(|c| c.is_whitespace())
+        .collect();
+
+    let mut out = Vec::with_capacity(lines.len() + 2);
+    for (i, line) in lines.iter().enumerate() {
+        if i == decl_at {
+            out.push(decl.clone());
+        }
+        if i == entry_at {
+            out.push(format!("{}{}", indent, entry));
+        }
+        out.push(line.to_string());
+    }
+
+    Ok(out.join("\n") + "\n")
+}
+
+/// Find where a new line belongs within the first contiguous block of lines
+/// matching `in_block`: after every line for which `before` holds.
+///
+/// Day numbers are zero padded so comparing the text orders them by day.
+fn insert_point<F, B>(lines: &[&str], in_block: F, before: B) -> Option<usize>
+where
+    F: Fn(&str) -> bool,
+    B: Fn(&str) -> bool,
+{
+    let mut at = lines.iter().position(|l| in_block(l))?;
+    while at < lines.len() && in_block(lines[at]) && before(lines[at]) {
+        at += 1;
+    }
+
+    Some(at)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env;
+    use std::process;
+
+    const MOD_RS: &str = "\
+pub mod day01;
+pub mod day03;
+
+pub fn all() -> Vec<&'static dyn Solution> {
+    vec![
+        &day01::Day01,
+        &day03::Day03,
+    ]
+}
+";
+
+    #[test]
+    fn render_fills_in_template() {
+        let rendered = render(8, "Memory Maneuver");
+        assert!(rendered.contains("pub struct Day08;"));
+        assert!(rendered.contains("impl Puzzle for Day08 {"));
+        assert!(rendered.contains("const DAY: u32 = 8;"));
+        assert!(rendered.contains("const TITLE: &'static str = \"Memory Maneuver\";"));
+        assert!(!rendered.contains("DayNN"));
+    }
+
+    #[test]
+    fn register_in_order() {
+        let expected = "\
+pub mod day01;
+pub mod day02;
+pub mod day03;
+
+pub fn all() -> Vec<&'static dyn Solution> {
+    vec![
+        &day01::Day01,
+        &day02::Day02,
+        &day03::Day03,
+    ]
+}
+";
+        assert_eq!(Ok(expected.to_string()), register(MOD_RS, 2));
+    }
+
+    #[test]
+    fn register_at_end() {
+        let registered = register(MOD_RS, 12).unwrap();
+        assert!(registered.contains("pub mod day03;\npub mod day12;\n"));
+        assert!(registered.contains("        &day03::Day03,\n        &day12::Day12,\n    ]"));
+    }
+
+    #[test]
+    fn register_at_start() {
+        let registered = register(&MOD_RS.replace("1", "2"), 1).unwrap();
+        assert!(registered.starts_with("pub mod day01;\npub mod day02;\n"));
+        assert!(registered.contains("    vec![\n        &day01::Day01,\n        &day02::Day02,\n"));
+    }
+
+    #[test]
+    fn register_refuses_duplicates() {
+        assert!(register(MOD_RS, 3).is_err());
+    }
+
+    #[test]
+    fn new_day_refuses_existing_file() {
+        let err = new_day(Path::new("."), 1, "Chronal Calibration").unwrap_err();
+        assert_eq!("./src/solutions/day01.rs already exists", err);
+    }
+
+    #[test]
+    fn new_day_rejects_bad_days() {
+        assert!(new_day(Path::new("."), 0, "").is_err());
+        assert!(new_day(Path::new("."), 26, "").is_err());
+    }
+
+    #[test]
+    fn new_day_stubs_out_example() {
+        let root = env::temp_dir().join(format!("aoc_2018_scaffold_{}", process::id()));
+        fs::create_dir_all(root.join(SOLUTIONS_DIR)).unwrap();
+        fs::write(root.join(SOLUTIONS_DIR).join("mod.rs"), MOD_RS).unwrap();
+
+        let created = new_day(&root, 8, "Memory Maneuver");
+        let answers = fs::read_to_string(root.join("tests/fixtures/day08/example.answers"));
+        fs::remove_dir_all(&root).unwrap();
+
+        let created = created.unwrap();
+        assert_eq!(
+            vec![
+                root.join("src/solutions/day08.rs"),
+                root.join("tests/fixtures/day08/example.txt"),
+                root.join("tests/fixtures/day08/example.answers"),
+            ],
+            created
+        );
+        let answers = answers.unwrap();
+        assert!(answers.contains("`8 1 42`"), "{}", answers);
+        assert!(answers.lines().all(|l| l.starts_with('#')), "{}", answers);
+    }
+}
@@ -1,41 +1,236 @@
-use std::collections::HashSet;
-use std::io::{BufRead, BufReader, Read};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::ops::Deref;
 
-pub fn work<R: Read>(r: R) {
-    let reader = BufReader::new(r);
+use super::{attempted, solved, Part, Puzzle};
+use error::Error;
+use input;
 
-    let changes: Vec<_> = reader
-        .lines()
-        .map(|l| l.unwrap().parse().unwrap())
-        .collect();
+const CHANGE_EXPECTED: &str = "a frequency change like +1 or -2";
+
+pub struct Day01;
+
+impl Puzzle for Day01 {
+    const DAY: u32 = 1;
+    const TITLE: &'static str = "Chronal Calibration";
+
+    type Input = Vec<isize>;
+
+    fn parse(&self, lines: &[String]) -> Result<Vec<isize>, Error> {
+        parse_changes(lines)
+    }
+
+    fn check(&self, lines: &[String]) -> Vec<Error> {
+        input::check_lines_with(lines, line_changes)
+    }
+
+    fn part1(&self, changes: &Vec<isize>) -> Part {
+        solved(solve_p1(changes))
+    }
 
-    info!("Part 1: {}", solve_p1(&changes));
-    info!("Part 2: {}", solve_p2(&changes));
+    fn part2(&self, changes: &Vec<isize>) -> Part {
+        attempted(solve_p2(changes))
+    }
+}
+
+/// Parse the frequency changes, which may be spread over lines or separated
+/// by commas or spaces as in the puzzle's examples, e.g. `+1, -2, +3, +1`.
+///
+/// Blank lines and anything after a `#` are ignored.
+pub fn parse_changes<T: Deref<Target = str>>(lines: &[T]) -> Result<Vec<isize>, Error> {
+    let changes = input::parse_lines_with(lines, line_changes)?;
+    Ok(changes.into_iter().flatten().collect())
+}
+
+/// The changes on a single line, failing on the first token that isn't one.
+fn line_changes(line: &str) -> Result<Vec<isize>, Error> {
+    let line = line.split('#').next().unwrap_or("");
+    line.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+        .map(|token| token.parse().map_err(|_| Error::parse(token, CHANGE_EXPECTED)))
+        .collect()
 }
 
-fn solve_p1(changes: &[isize]) -> isize {
+/// The frequency after applying every change once.
+pub fn solve_p1(changes: &[isize]) -> isize {
     changes.iter().sum()
 }
 
-fn solve_p2(changes: &[isize]) -> isize {
-    let mut freqs = HashSet::new();
+/// The first frequency reached twice when applying the changes over and
+/// over, starting from 0.
+pub fn solve_p2(changes: &[isize]) -> Result<isize, Error> {
+    first_repeat(changes).map(|r| r.frequency)
+}
+
+/// When a frequency is first reached for the second time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Repeat {
+    pub frequency: isize,
+    /// How many changes had been applied when it was reached again.
+    pub iteration: usize,
+    /// How many changes had been applied when it was first reached.
+    pub first_seen: usize,
+}
+
+/// Find the first frequency reached twice, and when.
+///
+/// Rather than stepping through every pass, this uses the fact that each
+/// pass shifts every frequency of the previous one by the same drift. A
+/// frequency from the first pass can only come back around to another one
+/// that's a whole number of drifts away, so grouping them by their remainder
+/// modulo the drift and looking at the nearest neighbour in the direction of
+/// the drift gives the first repeat directly.
+pub fn first_repeat(changes: &[isize]) -> Result<Repeat, Error> {
+    if changes.is_empty() {
+        return Err(Error::no_answer("there are no frequency changes"));
+    }
+
+    // the frequency at each point of the first pass, before each change
+    let mut firsts = Vec::with_capacity(changes.len());
+    let mut seen = HashMap::new();
     let mut f = 0;
-    let mut i = 0;
-    loop {
-        f += changes[i % changes.len()];
-        if freqs.contains(&f) {
-            break;
+    for (i, c) in changes.iter().enumerate() {
+        if let Some(&first_seen) = seen.get(&f) {
+            return Ok(Repeat {
+                frequency: f,
+                iteration: i,
+                first_seen,
+            });
+        }
+        seen.insert(f, i);
+        firsts.push(f);
+        f += c;
+    }
+
+    let n = changes.len();
+    let drift = f;
+    if drift == 0 {
+        // every pass is the same, so the first repeat is 0 at its end
+        return Ok(Repeat {
+            frequency: 0,
+            iteration: n,
+            first_seen: 0,
+        });
+    }
+
+    // frequencies a whole number of drifts apart, in the order the drift
+    // takes them through
+    let mut groups: Vec<(isize, isize, usize)> = firsts
+        .iter()
+        .enumerate()
+        .map(|(i, &f)| (f.rem_euclid(drift), f * drift.signum(), i))
+        .collect();
+    groups.sort_unstable();
+
+    // reaching the next frequency in a group takes `passes` whole passes,
+    // the earliest of those in time is the first repeat
+    groups
+        .windows(2)
+        .filter(|pair| pair[0].0 == pair[1].0)
+        .map(|pair| {
+            let (_, from, i) = pair[0];
+            let (_, to, j) = pair[1];
+            let passes = ((to - from) / drift.abs()) as usize;
+            Repeat {
+                frequency: firsts[j],
+                iteration: passes * n + i,
+                first_seen: j,
+            }
+        }).min_by_key(|r| r.iteration)
+        .ok_or_else(|| {
+            Error::no_answer(format!(
+                "the frequency drifts by {} every pass and never repeats",
+                drift
+            ))
+        })
+}
+
+/// How the frequency changes up to its first repeat, for debugging.
+#[derive(Debug, Clone, PartialEq)]
+pub struct History<'a> {
+    changes: &'a [isize],
+    /// How many changes make up a pass.
+    pub pass_len: usize,
+    /// How much a whole pass moves the frequency.
+    pub drift: isize,
+    pub repeat: Result<Repeat, Error>,
+}
+
+impl<'a> History<'a> {
+    /// The statistics for `changes`, leaving the trace to be stepped through
+    /// only if it's asked for, since it can be far too long to keep.
+    pub fn new(changes: &'a [isize]) -> Self {
+        History {
+            changes,
+            pass_len: changes.len(),
+            drift: solve_p1(changes),
+            repeat: first_repeat(changes),
+        }
+    }
+
+    /// How many changes the trace covers, up to the first repeat or through
+    /// one pass if the frequency never repeats.
+    pub fn iterations(&self) -> usize {
+        match self.repeat {
+            Ok(ref r) => r.iteration,
+            Err(_) => self.pass_len,
+        }
+    }
+
+    /// Every change applied, with the frequency after it.
+    pub fn trace(&self) -> impl Iterator<Item = (isize, isize)> + 'a {
+        let mut f = 0;
+        self.changes
+            .iter()
+            .cycle()
+            .take(self.iterations())
+            .map(move |&c| {
+                f += c;
+                (c, f)
+            })
+    }
+
+    /// The pass through the changes that an iteration is part of, counting
+    /// both from 1 and treating the start as part of the first pass.
+    pub fn pass(&self, iteration: usize) -> usize {
+        iteration.saturating_sub(1) / self.pass_len.max(1) + 1
+    }
+
+    /// Write the trace as CSV, one row per iteration including the start.
+    pub fn write_csv<W: Write>(&self, mut out: W) -> io::Result<()> {
+        writeln!(out, "iteration,pass,change,frequency\n0,1,,0")?;
+        for (i, (change, frequency)) in self.trace().enumerate() {
+            writeln!(out, "{},{},{:+},{}", i + 1, self.pass(i + 1), change, frequency)?;
         }
-        freqs.insert(f);
-        i += 1;
+        out.flush()
     }
+}
 
-    f
+impl<'a> fmt::Display for History<'a> {
+    /// The statistics, without the trace.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Changes per pass: {}", self.pass_len)?;
+        writeln!(f, "Drift per pass: {:+}", self.drift)?;
+        match self.repeat {
+            Ok(ref r) => writeln!(
+                f,
+                "First repeat: {} at iteration {} in pass {}, first reached at iteration {}",
+                r.frequency,
+                r.iteration,
+                self.pass(r.iteration),
+                r.first_seen
+            ),
+            Err(ref err) => writeln!(f, "First repeat: none, {}", err),
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use generate::Rng;
+    use std::collections::HashSet;
 
     #[test]
     fn positive_values() {
@@ -55,9 +250,116 @@ mod test {
         assert_eq!(-4, solve_p1(&input));
     }
 
+    #[test]
+    fn history() {
+        let changes = [1, -2, 3, 1];
+        let history = History::new(&changes);
+        assert_eq!(
+            Ok(Repeat {
+                frequency: 2,
+                iteration: 6,
+                first_seen: 3,
+            }),
+            history.repeat
+        );
+        assert_eq!(2, history.pass(6));
+        assert_eq!(
+            vec![(1, 1), (-2, -1), (3, 2), (1, 3), (1, 4), (-2, 2)],
+            history.trace().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "Changes per pass: 4\nDrift per pass: +3\n\
+             First repeat: 2 at iteration 6 in pass 2, first reached at iteration 3\n",
+            history.to_string()
+        );
+
+        let mut csv = Vec::new();
+        history.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(csv.starts_with("iteration,pass,change,frequency\n0,1,,0\n1,1,+1,1\n"));
+        assert!(csv.ends_with("\n6,2,-2,2\n"));
+    }
+
+    #[test]
+    fn history_without_repeat() {
+        let history = History::new(&[1, 1]);
+        assert!(history.repeat.is_err());
+        assert_eq!(2, history.trace().count());
+    }
+
+    #[test]
+    fn history_of_a_slow_repeat() {
+        // repeats after about two trillion changes, far too many to keep
+        let changes = [1_000_000_000_000, -999_999_999_999];
+        let history = History::new(&changes);
+        assert_eq!(2_000_000_000_000, history.iterations());
+        assert_eq!(
+            vec![(1_000_000_000_000, 1_000_000_000_000), (-999_999_999_999, 1)],
+            history.trace().take(2).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn example_notation() {
+        let lines = ["+1, -2, +3, +1", "", "# more", "-1 +1  # trailing", "-7,"];
+        assert_eq!(Ok(vec![1, -2, 3, 1, -1, 1, -7]), parse_changes(&lines));
+    }
+
+    #[test]
+    fn reports_bad_token() {
+        let lines = ["+1, -2", "+3, +x, +1"];
+        assert_eq!(
+            Err(Error::parse("+x", CHANGE_EXPECTED).at_line(2)),
+            parse_changes(&lines)
+        );
+    }
+
     #[test]
     fn repeat_test() {
         let changes = vec![1, -2, 3, 1];
-        assert_eq!(2, solve_p2(&changes));
+        assert_eq!(Ok(2), solve_p2(&changes));
+    }
+
+    #[test]
+    fn repeats_start() {
+        assert_eq!(Ok(0), solve_p2(&[1, -1]));
+    }
+
+    #[test]
+    fn never_repeats() {
+        assert_eq!(
+            Err(Error::no_answer("the frequency drifts by 1 every pass and never repeats")),
+            solve_p2(&[1])
+        );
+        assert!(solve_p2(&[]).is_err());
+    }
+
+    /// Step through the passes, giving up after `limit` changes, returning
+    /// the first repeat and how many changes it took.
+    fn step_through(changes: &[isize], limit: usize) -> Option<(isize, usize)> {
+        let mut seen = HashSet::new();
+        let mut f = 0;
+        for (i, c) in changes.iter().cycle().take(limit).enumerate() {
+            seen.insert(f);
+            f += c;
+            if seen.contains(&f) {
+                return Some((f, i + 1));
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn matches_stepping_through() {
+        for seed in 0..200 {
+            let mut rng = Rng::new(seed);
+            let len = 1 + rng.below(20);
+            let changes: Vec<isize> = (0..len).map(|_| rng.between(-10, 10)).collect();
+            // frequencies stay within 200 of 0, so any repeat comes well
+            // within this many changes
+            let expected = step_through(&changes, 10_000);
+            let found = first_repeat(&changes).ok().map(|r| (r.frequency, r.iteration));
+            assert_eq!(expected, found, "{:?}", changes);
+        }
     }
 }
@@ -1,27 +1,77 @@
-use std::io::{BufRead, BufReader, Read};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::ops::Deref;
 
-pub fn work<R: Read>(r: R) {
-    let reader = BufReader::new(r);
+use super::{attempted, solved, Part, Puzzle};
+use error::Error;
+use input;
 
-    let boxes: Vec<_> = reader.lines().map(|res| res.unwrap()).collect();
+const ID_EXPECTED: &str = "a box ID";
 
-    println!("Part 1: {}", checksum(&boxes));
-    println!("Part 2: {}", off_by_one(&boxes));
+pub struct Day02;
+
+impl Puzzle for Day02 {
+    const DAY: u32 = 2;
+    const TITLE: &'static str = "Inventory Management System";
+
+    type Input = Vec<String>;
+
+    /// Every non-empty line is an ID, of any characters.
+    fn parse(&self, lines: &[String]) -> Result<Vec<String>, Error> {
+        input::parse_lines_with(lines, parse_id)
+    }
+
+    /// Also reports IDs of a different length from the first, which part 2
+    /// can't compare.
+    fn check(&self, lines: &[String]) -> Vec<Error> {
+        let first = lines.iter().find(|l| !l.is_empty()).map_or("", |l| &l[..]);
+        input::check_lines_with(lines, |l| parse_id(l).and_then(|id| same_length_as(first, &id)))
+    }
+
+    fn part1(&self, boxes: &Vec<String>) -> Part {
+        solved(checksum(boxes))
+    }
+
+    fn part2(&self, boxes: &Vec<String>) -> Part {
+        attempted(same_length(boxes).and_then(|_| off_by_one(boxes)))
+    }
 }
 
-fn checksum<T: Deref<Target = str>>(boxes: &[T]) -> usize {
+fn parse_id(line: &str) -> Result<String, Error> {
+    if line.is_empty() {
+        return Err(Error::parse(line, ID_EXPECTED));
+    }
+    Ok(line.to_string())
+}
+
+/// Checks every ID has as many characters as the first, which comparing
+/// them position by position in part 2 needs.
+fn same_length(boxes: &[String]) -> Result<(), Error> {
+    let first = boxes.first().map_or("", |id| &id[..]);
+    input::parse_lines_with(boxes, |id| same_length_as(first, id)).map(|_| ())
+}
+
+fn same_length_as(first: &str, id: &str) -> Result<(), Error> {
+    if id.chars().count() != first.chars().count() {
+        return Err(Error::length_mismatch(first, id));
+    }
+    Ok(())
+}
+
+/// The number of IDs containing a character exactly twice multiplied by the
+/// number containing a character exactly three times.
+///
+/// Any characters are counted, not just the lowercase letters of the puzzle.
+pub fn checksum<T: Deref<Target = str>>(boxes: &[T]) -> usize {
     let mut doubles = 0;
     let mut triples = 0;
     for s in boxes {
-        let mut letters = vec![0u8; 26];
-        for b in s.bytes() {
-            assert!(b >= 0x61 && b <= 0x7A);
-            letters[(b - 0x61) as usize] += 1;
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        for c in s.chars() {
+            *counts.entry(c).or_insert(0) += 1;
         }
 
-        let found_double = letters.iter().any(|&n| n == 2);
-        let found_triple = letters.iter().any(|&n| n == 3);
+        let found_double = counts.values().any(|&n| n == 2);
+        let found_triple = counts.values().any(|&n| n == 3);
 
         if found_double {
             doubles += 1;
@@ -34,42 +84,190 @@ fn checksum<T: Deref<Target = str>>(boxes: &[T]) -> usize {
     doubles * triples
 }
 
-fn off_by_one<T: Deref<Target = str>>(boxes: &[T]) -> String {
-    let mut ids = None;
-    for j in 0..boxes.len() {
-        for k in (j + 1)..boxes.len() {
-            if distance(&boxes[j], &boxes[k]) == 1 {
-                ids = Some((boxes[j].to_string(), boxes[k].to_string()));
-                break;
+/// The letters shared by the two IDs that differ in exactly one position.
+///
+/// Each ID is hashed once per position with that position left out, so two
+/// IDs differing only there share a key. That takes O(n·L²) for `n` IDs of
+/// length `L` rather than comparing every pair. If several pairs match, the
+/// one whose later ID comes first wins, then the one whose earlier ID does.
+pub fn off_by_one<T: Deref<Target = str>>(boxes: &[T]) -> Result<String, Error> {
+    // the first ID seen with each position masked out, keyed by both
+    let mut masked: HashMap<(usize, String), usize> = HashMap::new();
+    let mut seen = HashSet::new();
+    for (k, id) in boxes.iter().enumerate() {
+        // a copy of an earlier ID matches nothing that one didn't
+        if !seen.insert(&**id) {
+            continue;
+        }
+
+        let chars: Vec<char> = id.chars().collect();
+        let keys: Vec<(usize, String)> = (0..chars.len())
+            .map(|p| (p, chars[..p].iter().chain(&chars[p + 1..]).collect()))
+            .collect();
+        if let Some((_, common)) = keys
+            .iter()
+            .filter_map(|key| masked.get(key).map(|&j| (j, &key.1)))
+            .min()
+        {
+            return Ok(common.clone());
+        }
+        for key in keys {
+            masked.entry(key).or_insert(k);
+        }
+    }
+
+    Err(Error::no_answer("no two box IDs differ by exactly one letter"))
+}
+
+/// Two IDs within some distance of each other.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NearPair {
+    pub left: String,
+    pub right: String,
+    /// The letters they share, in order.
+    pub common: String,
+    /// The positions where they differ.
+    pub differences: Vec<usize>,
+}
+
+/// Every pair of IDs of the same length differing in at most `k` positions,
+/// ordered by where the pair's IDs appear in `boxes`.
+///
+/// Two IDs within `k` of each other must match exactly on at least one of
+/// `k + 1` blocks of positions, so only IDs sharing a block are compared.
+pub fn near_pairs<T: Deref<Target = str>>(boxes: &[T], k: usize) -> Vec<NearPair> {
+    let ids: Vec<Vec<char>> = boxes.iter().map(|id| id.chars().collect()).collect();
+
+    let mut buckets: HashMap<(usize, usize, &[char]), Vec<usize>> = HashMap::new();
+    for (i, id) in ids.iter().enumerate() {
+        let len = id.len();
+        if len <= k {
+            // every pair this short is within k, so compare them all
+            buckets.entry((len, 0, &[])).or_default().push(i);
+            continue;
+        }
+        for b in 0..=k {
+            let block = &id[b * len / (k + 1)..(b + 1) * len / (k + 1)];
+            buckets.entry((len, b, block)).or_default().push(i);
+        }
+    }
+
+    let mut candidates = BTreeSet::new();
+    for bucket in buckets.values() {
+        for (n, &j) in bucket.iter().enumerate() {
+            for &i in &bucket[..n] {
+                candidates.insert((i, j));
             }
         }
     }
-    let (left, right) = ids.expect("bad input, could not find ids off by one");
 
-    left.chars()
-        .zip(right.chars())
-        .filter(|&t| t.0 == t.1)
-        .map(|t| t.0)
-        .collect()
+    candidates
+        .into_iter()
+        .filter_map(|(i, j)| {
+            let (left, right) = (&ids[i], &ids[j]);
+            let differences: Vec<usize> =
+                (0..left.len()).filter(|&p| left[p] != right[p]).collect();
+            if differences.len() > k {
+                return None;
+            }
+            Some(NearPair {
+                left: left.iter().collect(),
+                right: right.iter().collect(),
+                common: (0..left.len())
+                    .filter(|&p| left[p] == right[p])
+                    .map(|p| left[p])
+                    .collect(),
+                differences,
+            })
+        }).collect()
 }
 
-/// Return the number of characters that are different between the strings.
+/// Groups of IDs linked by chains of pairs within `k` of each other, each
+/// in the order they appear in `boxes`. IDs with no near duplicate are left
+/// out.
+pub fn clusters<T: Deref<Target = str>>(boxes: &[T], k: usize) -> Vec<Vec<String>> {
+    // where each distinct ID first appears
+    let index: HashMap<&str, usize> = boxes
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, id)| (&**id, i))
+        .collect();
+
+    // union-find, with each cluster's root being its earliest ID
+    let mut parent: Vec<usize> = (0..boxes.len()).collect();
+    for pair in near_pairs(boxes, k) {
+        let a = root(&mut parent, index[pair.left.as_str()]);
+        let b = root(&mut parent, index[pair.right.as_str()]);
+        parent[a.max(b)] = a.min(b);
+    }
+
+    let mut groups: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    for id in boxes {
+        let r = root(&mut parent, index[&**id]);
+        groups.entry(r).or_default().push(id.to_string());
+    }
+    groups.into_values().filter(|g| g.len() > 1).collect()
+}
+
+/// The root of the union-find tree `i` is in, flattening the path to it.
+fn root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// How to measure the distance between two IDs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Distance {
+    /// The number of positions with different characters, which only makes
+    /// sense for IDs of the same length.
+    Hamming,
+    /// The fewest characters inserted, removed or replaced to turn one ID
+    /// into the other.
+    Levenshtein,
+}
+
+/// The distance between two IDs, counting characters rather than bytes.
 ///
-/// Will panic if the strings are not the same length.
-fn distance(left: &str, right: &str) -> usize {
-    if left.len() != right.len() {
-        panic!("variable string length not supported!");
+/// Fails for `Distance::Hamming` if the IDs are different lengths.
+pub fn distance(left: &str, right: &str, metric: Distance) -> Result<usize, Error> {
+    match metric {
+        Distance::Hamming => {
+            if left.chars().count() != right.chars().count() {
+                return Err(Error::length_mismatch(left, right));
+            }
+            Ok(left.chars().zip(right.chars()).filter(|&t| t.0 != t.1).count())
+        }
+        Distance::Levenshtein => Ok(levenshtein(left, right)),
     }
+}
 
-    left.chars()
-        .zip(right.chars())
-        .filter(|&t| t.0 != t.1)
-        .count()
+/// The edit distance between two strings, keeping only one row of the usual
+/// table at a time.
+fn levenshtein(left: &str, right: &str) -> usize {
+    let right: Vec<char> = right.chars().collect();
+    let mut row: Vec<usize> = (0..=right.len()).collect();
+    for (i, l) in left.chars().enumerate() {
+        // the distance from the previous row's diagonal, before it's replaced
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &r) in right.iter().enumerate() {
+            let replaced = diagonal + if l == r { 0 } else { 1 };
+            diagonal = row[j + 1];
+            row[j + 1] = replaced.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[right.len()]
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use generate::Rng;
+    use solutions::{Parts, Solution};
 
     #[test]
     fn one_each() {
@@ -78,20 +276,175 @@ mod test {
     }
 
     #[test]
-    fn example_checksum() {
-        let boxes = vec![
-            "abcdef", "bababc", "abbcde", "abcccd", "aabcdd", "abcdee", "ababab",
-        ];
+    fn checksum_counts_any_characters() {
+        let boxes = vec!["ÄäÄ", "ééé", "a  b", "AaAA", "xyz"];
+        assert_eq!(4, checksum(&boxes));
+    }
+
+    #[test]
+    fn hamming_distance() {
+        assert_eq!(Ok(1), distance("kitten", "sitten", Distance::Hamming));
+        // the same number of characters, but not of bytes
+        assert_eq!(Ok(1), distance("naïve", "naive", Distance::Hamming));
+        assert_eq!(
+            Err(Error::length_mismatch("abc", "abcd")),
+            distance("abc", "abcd", Distance::Hamming)
+        );
+    }
+
+    #[test]
+    fn levenshtein_distance() {
+        assert_eq!(Ok(3), distance("kitten", "sitting", Distance::Levenshtein));
+        assert_eq!(Ok(3), distance("", "abc", Distance::Levenshtein));
+        assert_eq!(Ok(0), distance("日本", "日本", Distance::Levenshtein));
+        assert_eq!(Ok(1), distance("日本", "日本語", Distance::Levenshtein));
+        assert_eq!(Ok(1), distance("fghij", "fguij", Distance::Levenshtein));
+    }
+
+    #[test]
+    fn parse_any_characters() {
+        let lines: Vec<String> = ["ÄäÄb", "ééé", "a  b", "AaAA", "日本語"]
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+        let (answer, _) = Day02.timed(&lines, Parts::Both);
+        assert_eq!(solved(4), answer.part1);
+        assert_eq!(
+            vec![
+                Error::length_mismatch("ÄäÄb", "ééé").at_line(2).in_day(2),
+                Error::length_mismatch("ÄäÄb", "日本語").at_line(5).in_day(2),
+            ],
+            Solution::check(&Day02, &lines)
+        );
+        assert_eq!(
+            Some(Err(Error::length_mismatch("ÄäÄb", "ééé").at_line(2).in_day(2))),
+            answer.part2
+        );
+
+        let lines = vec!["ab".to_string(), String::new()];
+        assert_eq!(
+            Err(Error::parse("", ID_EXPECTED).at_line(2)),
+            Day02.parse(&lines)
+        );
+    }
+
+    #[test]
+    fn part2_with_characters_of_any_width() {
+        let lines: Vec<String> = ["naïve", "naive", "bravo"].iter().map(|l| l.to_string()).collect();
+        let (answer, _) = Day02.timed(&lines, Parts::Part2);
+        assert_eq!(solved("nave"), answer.part2);
+    }
+
+    /// Compare every pair, kept as a reference for `off_by_one`.
+    fn pairwise<T: Deref<Target = str>>(boxes: &[T]) -> Option<String> {
+        for k in 0..boxes.len() {
+            for j in 0..k {
+                if distance(&boxes[j], &boxes[k], Distance::Hamming) == Ok(1) {
+                    let common = boxes[j]
+                        .chars()
+                        .zip(boxes[k].chars())
+                        .filter(|&(a, b)| a == b)
+                        .map(|(a, _)| a)
+                        .collect();
+                    return Some(common);
+                }
+            }
+        }
+        None
+    }
 
-        assert_eq!(12, checksum(&boxes));
+    #[test]
+    fn off_by_one_matches_pairwise() {
+        for seed in 0..200 {
+            // a small alphabet makes near duplicates, and duplicates, common
+            let mut rng = Rng::new(seed);
+            let len = 1 + rng.below(5);
+            let boxes: Vec<String> = (0..1 + rng.below(20))
+                .map(|_| (0..len).map(|_| (b'a' + rng.below(3) as u8) as char).collect())
+                .collect();
+            assert_eq!(pairwise(&boxes), off_by_one(&boxes).ok(), "{:?}", boxes);
+        }
+    }
+
+    #[test]
+    fn off_by_one_ignores_copies() {
+        let boxes = vec!["abc", "abc", "abd"];
+        assert_eq!(Ok("ab".to_string()), off_by_one(&boxes));
+        assert!(off_by_one(&["abc", "abc"]).is_err());
     }
 
     #[test]
-    fn example_off_by_one() {
+    fn near_pairs_example() {
         let boxes = vec![
             "abcde", "fghij", "klmno", "pqrst", "fguij", "axcye", "wvxyz",
         ];
+        assert_eq!(
+            vec![NearPair {
+                left: "fghij".to_string(),
+                right: "fguij".to_string(),
+                common: "fgij".to_string(),
+                differences: vec![2],
+            }],
+            near_pairs(&boxes, 1)
+        );
 
-        assert_eq!("fgij", off_by_one(&boxes));
+        let pairs: Vec<_> = near_pairs(&boxes, 2)
+            .into_iter()
+            .map(|p| (p.left, p.right, p.differences))
+            .collect();
+        assert_eq!(
+            vec![
+                ("abcde".to_string(), "axcye".to_string(), vec![1, 3]),
+                ("fghij".to_string(), "fguij".to_string(), vec![2]),
+            ],
+            pairs
+        );
+    }
+
+    #[test]
+    fn near_pairs_match_pairwise() {
+        for seed in 0..100 {
+            let mut rng = Rng::new(seed);
+            let boxes: Vec<String> = (0..1 + rng.below(15))
+                .map(|_| {
+                    let len = 1 + rng.below(6);
+                    (0..len).map(|_| (b'a' + rng.below(3) as u8) as char).collect()
+                }).collect();
+            for k in 0..4 {
+                let mut expected = Vec::new();
+                for i in 0..boxes.len() {
+                    for j in i + 1..boxes.len() {
+                        let d = distance(&boxes[i], &boxes[j], Distance::Hamming);
+                        if d.is_ok_and(|d| d <= k) {
+                            expected.push((boxes[i].clone(), boxes[j].clone()));
+                        }
+                    }
+                }
+                let found: Vec<_> = near_pairs(&boxes, k)
+                    .into_iter()
+                    .map(|p| (p.left, p.right))
+                    .collect();
+                assert_eq!(expected, found, "k = {}, {:?}", k, boxes);
+            }
+        }
+    }
+
+    #[test]
+    fn near_duplicate_clusters() {
+        let boxes = vec!["abc", "xyz", "abd", "qqq", "xbd", "xyz"];
+        assert_eq!(
+            vec![
+                vec!["abc".to_string(), "abd".to_string(), "xbd".to_string()],
+                vec!["xyz".to_string(), "xyz".to_string()],
+            ],
+            clusters(&boxes, 1)
+        );
+        assert!(clusters(&["abc", "xyz"], 2).is_empty());
+    }
+
+    #[test]
+    fn off_by_one_without_match() {
+        let boxes = vec!["abc", "xyz"];
+        assert!(off_by_one(&boxes).is_err());
     }
 }
@@ -1,75 +1,74 @@
 use std::cmp::max;
-use std::io::{BufRead, BufReader, Read};
-use std::num::ParseIntError;
 use std::ops::Deref;
 
-pub fn work<R: Read>(r: R) {
-    let reader = BufReader::new(r);
+use super::{attempted, solved, Part, Puzzle};
+use error::Error;
+use grid::Grid;
+use input;
 
-    let lines: Vec<_> = reader.lines().map(|res| res.unwrap()).collect();
+pub struct Day03;
 
-    match count_overlapping(&lines) {
-        Ok(over) => info!("Part 1: {}", over),
-        Err(err) => error!("Failed part 1: {}", err),
+impl Puzzle for Day03 {
+    const DAY: u32 = 3;
+    const TITLE: &'static str = "No Matter How You Slice It";
+
+    type Input = Vec<Claim>;
+
+    fn parse(&self, lines: &[String]) -> Result<Vec<Claim>, Error> {
+        parse_claims(lines)
     }
-    match find_non_overlap(&lines) {
-        Ok(id) => info!("Part 2: {}", id),
-        Err(err) => error!("Failed part 1: {}", err),
+
+    fn check(&self, lines: &[String]) -> Vec<Error> {
+        input::check_lines_with(lines, Claim::try_parse)
     }
-}
 
-fn count_overlapping<T: Deref<Target = str>>(lines: &[T]) -> Result<usize, String> {
-    let mut claims = Vec::new();
+    fn part1(&self, claims: &Vec<Claim>) -> Part {
+        solved(count_overlapping(claims))
+    }
 
-    for line in lines {
-        let claim = Claim::try_parse(line)?;
-        claims.push(claim);
+    fn part2(&self, claims: &Vec<Claim>) -> Part {
+        attempted(find_non_overlap(claims))
     }
+}
 
+/// Parse one claim per line.
+pub fn parse_claims<T: Deref<Target = str>>(lines: &[T]) -> Result<Vec<Claim>, Error> {
+    let claims = input::parse_lines_with(lines, Claim::try_parse)?;
     debug!("Got {} claims", claims.len());
 
+    Ok(claims)
+}
+
+/// The number of square inches covered by two or more claims.
+pub fn count_overlapping(claims: &[Claim]) -> usize {
     let mut height = 0;
     let mut width = 0;
-    for c in &claims {
+    for c in claims {
         height = max(height, c.top + c.height);
         width = max(width, c.left + c.width);
     }
 
     debug!("Generating grid {}x{}", height, width);
 
-    let mut grid = vec![vec![0u8; width]; height];
-    for c in &claims {
-        for h in 0..c.height {
-            for w in 0..c.width {
-                grid[c.top + h][c.left + w] += 1;
+    // only whether a square has two or more claims matters, so the count
+    // stops there rather than overflowing
+    let mut grid = Grid::new(width, height, 0u8);
+    for c in claims {
+        for y in c.top..c.top + c.height {
+            for x in c.left..c.left + c.width {
+                let square = &mut grid[(x, y)];
+                *square = square.saturating_add(1);
             }
         }
     }
 
     debug!("Grid generated, counting overlap");
 
-    let mut overlap = 0;
-    for row in &grid {
-        for point in row {
-            if point >= &2 {
-                overlap += 1;
-            }
-        }
-    }
-
-    Ok(overlap)
+    grid.iter().filter(|&(_, &n)| n >= 2).count()
 }
 
-fn find_non_overlap<T: Deref<Target = str>>(lines: &[T]) -> Result<usize, String> {
-    let mut claims = Vec::new();
-
-    for line in lines {
-        let claim = Claim::try_parse(line)?;
-        claims.push(claim);
-    }
-
-    debug!("Got {} claims", claims.len());
-
+/// The id of the only claim that overlaps no other.
+pub fn find_non_overlap(claims: &[Claim]) -> Result<usize, Error> {
     let mut set: std::collections::HashSet<_> = claims.iter().map(|c| c.id).collect();
 
     debug!("Collected claim ids into set of size: {}", set.len());
@@ -85,22 +84,22 @@ fn find_non_overlap<T: Deref<Target = str>>(lines: &[T]) -> Result<usize, String
 
     debug!("Non-intersecting claims: {:?}", set);
     if set.len() != 1 {
-        return Err(format!(
+        return Err(Error::no_answer(format!(
             "non-intersecting claims not exactly 1: {}",
             set.len()
-        ));
+        )));
     }
 
     Ok(*set.iter().next().unwrap())
 }
 
 #[derive(Debug, PartialEq)]
-struct Claim {
-    id: usize,
-    left: usize,
-    top: usize,
-    width: usize,
-    height: usize,
+pub struct Claim {
+    pub id: usize,
+    pub left: usize,
+    pub top: usize,
+    pub width: usize,
+    pub height: usize,
 }
 
 impl Claim {
@@ -108,35 +107,21 @@ impl Claim {
     ///
     /// Expected format is "#<id> @ <left>,<top>: <width>x<height>".
     /// For example: "#123 @ 3,2: 5x4" is a valid claim.
-    fn try_parse(s: &str) -> Result<Self, String> {
+    pub fn try_parse(s: &str) -> Result<Self, Error> {
         let mut iter = s
             .split(|c| !char::is_digit(c, 10))
             .filter(|s| !s.is_empty());
-        let id = iter
-            .next()
-            .ok_or("couldn't get id")?
-            .parse()
-            .map_err(|e: ParseIntError| e.to_string())?;
-        let left = iter
-            .next()
-            .ok_or("failed to get left edge")?
-            .parse()
-            .map_err(|e: ParseIntError| e.to_string())?;
-        let top = iter
-            .next()
-            .ok_or("failed to get top edge")?
-            .parse()
-            .map_err(|e: ParseIntError| e.to_string())?;
-        let width = iter
-            .next()
-            .ok_or("failed to get width")?
-            .parse()
-            .map_err(|e: ParseIntError| e.to_string())?;
-        let height = iter
-            .next()
-            .ok_or("failed to get height")?
-            .parse()
-            .map_err(|e: ParseIntError| e.to_string())?;
+        let mut next = |field: &str| {
+            iter.next().and_then(|n| n.parse().ok()).ok_or_else(|| {
+                let expected = format!("a claim like \"#<id> @ <left>,<top>: <width>x<height>\" (no {})", field);
+                Error::parse(s, expected)
+            })
+        };
+        let id = next("id")?;
+        let left = next("left edge")?;
+        let top = next("top edge")?;
+        let width = next("width")?;
+        let height = next("height")?;
 
         Ok(Claim {
             id,
@@ -148,31 +133,12 @@ impl Claim {
     }
 
     /// Returns true iff self intersects other.
-    fn intersects(&self, other: &Self) -> bool {
-        if self.left < other.left {
-            // left of other
-            if self.left + self.width >= other.left {
-                return true;
-            }
-        } else {
-            // right of other
-            if other.left + other.width >= self.left {
-                return true;
-            }
-        }
-        if self.top < other.top {
-            // above other
-            if self.top + self.height >= other.top {
-                return true;
-            }
-        } else {
-            // below other
-            if other.top + other.height >= self.top {
-                return true;
-            }
-        }
+    pub fn intersects(&self, other: &Self) -> bool {
+        // claims overlap only when they overlap along both axes
+        let horizontal = self.left < other.left + other.width && other.left < self.left + self.width;
+        let vertical = self.top < other.top + other.height && other.top < self.top + self.height;
 
-        return false;
+        horizontal && vertical
     }
 }
 
@@ -205,15 +171,25 @@ mod test {
     }
 
     #[test]
-    fn overlapping_example() {
-        let boxes = vec!["#1 @ 1,3: 4x4", "#2 @ 3,1: 4x4", "#3 @ 5,5: 2x2"];
-        assert_eq!(Ok(4), count_overlapping(&boxes));
+    fn test_claim_try_parse_missing_field() {
+        let lines = vec!["#1 @ 2,3: 4x5", "#2 @ 2,3: 4x"];
+        assert_eq!(
+            r##"line 2: expected a claim like "#<id> @ <left>,<top>: <width>x<height>" (no height), got "#2 @ 2,3: 4x""##,
+            parse_claims(&lines).unwrap_err().to_string()
+        );
     }
 
     #[test]
-    fn non_intersecting_claim_example() {
-        let boxes = vec!["#1 @ 1,3: 4x4", "#2 @ 3,1: 4x4", "#3 @ 5,5: 2x2"];
-        assert_eq!(Ok(3), find_non_overlap(&boxes));
+    fn many_claims_on_one_square() {
+        let claims: Vec<_> = (1..=300)
+            .map(|id| Claim {
+                id,
+                left: 1,
+                top: 1,
+                width: 2,
+                height: 1,
+            }).collect();
+        assert_eq!(2, count_overlapping(&claims));
     }
 
     #[test]
@@ -247,4 +223,46 @@ mod test {
         assert!(!c1.intersects(&c2));
         assert!(!c2.intersects(&c1));
     }
+
+    #[test]
+    fn overlapping_on_one_axis_only() {
+        // the same columns, but far apart vertically
+        let c1 = Claim {
+            id: 1,
+            left: 2,
+            top: 2,
+            width: 2,
+            height: 2,
+        };
+        let c2 = Claim {
+            id: 2,
+            left: 3,
+            top: 100,
+            width: 2,
+            height: 2,
+        };
+        assert!(!c1.intersects(&c2));
+        assert!(!c2.intersects(&c1));
+    }
+
+    #[test]
+    fn touching_claims() {
+        // c2 starts in the column right after c1 ends, sharing no square
+        let c1 = Claim {
+            id: 1,
+            left: 2,
+            top: 2,
+            width: 2,
+            height: 2,
+        };
+        let c2 = Claim {
+            id: 2,
+            left: 4,
+            top: 2,
+            width: 2,
+            height: 2,
+        };
+        assert!(!c1.intersects(&c2));
+        assert!(!c2.intersects(&c1));
+    }
 }
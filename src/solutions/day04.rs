@@ -1,87 +1,158 @@
-use std::io::{BufRead, BufReader, Read};
+use std::collections::HashMap;
 use std::ops::Deref;
 
 use regex::Regex;
 
+use super::{attempted, Part, Puzzle};
+use error::Error;
+use input;
+
 const DATE_REG: &str = r"\[(\d{4})-(\d{2})-(\d{2}) (\d{2}):(\d{2})\]";
 
-pub fn work<R: Read>(r: R) {
-    let reader = BufReader::new(r);
+pub struct Day04;
+
+impl Puzzle for Day04 {
+    const DAY: u32 = 4;
+    const TITLE: &'static str = "Repose Record";
+
+    type Input = Vec<Record>;
+
+    fn parse(&self, lines: &[String]) -> Result<Vec<Record>, Error> {
+        parse_records(lines)
+    }
 
-    let lines: Vec<_> = reader.lines().map(|res| res.unwrap()).collect();
+    fn check(&self, lines: &[String]) -> Vec<Error> {
+        input::check_lines_with(lines, Record::from_line)
+    }
+
+    fn part1(&self, records: &Vec<Record>) -> Part {
+        attempted(most_asleep_guard_minute(records))
+    }
+}
 
-    println!("Part 1: {}", most_asleep_guard_minute(&lines));
+/// Parse the records, putting them in chronological order.
+pub fn parse_records<T: Deref<Target = str>>(lines: &[T]) -> Result<Vec<Record>, Error> {
+    let mut records = input::parse_lines_with(lines, Record::from_line)?;
+    records.sort_by(|a, b| a.time.cmp(&b.time));
+    Ok(records)
 }
 
-fn most_asleep_guard_minute<T: Deref<Target = str>>(lines: &[T]) -> usize {
-    let records: Vec<_> = lines.iter().map(|s| Record::from_line(s)).collect();
+/// Find the guard who sleeps the most and the minute they are most often
+/// asleep, returning the guard id multiplied by that minute.
+pub fn most_asleep_guard_minute(records: &[Record]) -> Result<usize, Error> {
+    // minutes asleep during the midnight hour, per guard
+    let mut asleep: HashMap<usize, [usize; 60]> = HashMap::new();
+    let mut guard = None;
+    let mut fell_asleep = None;
+    for rec in records {
+        match rec.act {
+            Action::Guard(id) => {
+                guard = Some(id);
+                fell_asleep = None;
+            }
+            Action::Sleep => fell_asleep = Some(rec.time.minute),
+            Action::WakeUp => {
+                let id = guard
+                    .ok_or_else(|| Error::no_answer("a guard woke up before any shift began"))?;
+                let fell_asleep = fell_asleep.take().ok_or_else(|| {
+                    Error::no_answer(format!("guard #{} woke up without falling asleep", id))
+                })?;
+                if rec.time.hour != 0 || rec.time.minute < fell_asleep {
+                    return Err(Error::no_answer(format!(
+                        "guard #{} woke up outside the midnight hour or before falling asleep",
+                        id
+                    )));
+                }
+                let minutes = asleep.entry(id).or_insert([0; 60]);
+                for m in &mut minutes[fell_asleep..rec.time.minute] {
+                    *m += 1;
+                }
+            }
+        }
+    }
+
+    let (guard, minutes) = asleep
+        .iter()
+        .max_by_key(|(_, mins)| mins.iter().sum::<usize>())
+        .ok_or_else(|| Error::no_answer("no guard ever fell asleep"))?;
+    let (minute, _) = minutes
+        .iter()
+        .enumerate()
+        .max_by_key(|&(_, n)| n)
+        .unwrap();
 
-    0
+    Ok(guard * minute)
 }
 
-#[derive(PartialEq, Debug)]
-struct Date {
-    year: usize,
-    month: usize,
-    day: usize,
-    hour: usize,
-    minute: usize,
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Date {
+    pub year: usize,
+    pub month: usize,
+    pub day: usize,
+    pub hour: usize,
+    pub minute: usize,
 }
 
 impl Date {
-    fn from_line(line: &str) -> Self {
+    pub fn from_line(line: &str) -> Result<Self, Error> {
+        let bad = || Error::parse(line, "a record starting with a timestamp like [1518-11-01 00:05]");
         let re = Regex::new(DATE_REG).unwrap();
-        let caps = re.captures(line).unwrap();
-        let date_nums: Vec<usize> = caps
+        let caps = re.captures(line).ok_or_else(bad)?;
+        let date_nums = caps
             .iter()
             .skip(1) // first item is match for entire regex
-            .map(|m| m.unwrap().as_str().parse().unwrap())
-            .collect();
-        Date {
+            .map(|m| m.unwrap().as_str().parse().map_err(|_| bad()))
+            .collect::<Result<Vec<usize>, _>>()?;
+        if date_nums[4] >= 60 {
+            return Err(bad());
+        }
+
+        Ok(Date {
             year: date_nums[0],
             month: date_nums[1],
             day: date_nums[2],
             hour: date_nums[3],
             minute: date_nums[4],
-        }
+        })
     }
 }
 
 #[derive(PartialEq, Debug)]
-enum Action {
+pub enum Action {
     Sleep,
     WakeUp,
     Guard(usize),
 }
 
 impl Action {
-    fn from_line(line: &str) -> Self {
+    pub fn from_line(line: &str) -> Result<Self, Error> {
         let wake_re = Regex::new(r"wakes up").unwrap();
         let sleep_re = Regex::new(r"falls asleep").unwrap();
         if wake_re.is_match(line) {
-            Action::WakeUp
+            Ok(Action::WakeUp)
         } else if sleep_re.is_match(line) {
-            Action::Sleep
+            Ok(Action::Sleep)
         } else {
+            let bad = || Error::parse(line, "\"wakes up\", \"falls asleep\" or \"Guard #<id> begins shift\"");
             let guard_re = Regex::new(r"Guard #(\d+)").unwrap();
-            let caps = guard_re.captures(line).unwrap();
-            let guard = caps[1].parse().unwrap();
-            Action::Guard(guard)
+            let caps = guard_re.captures(line).ok_or_else(bad)?;
+            let guard = caps[1].parse().map_err(|_| bad())?;
+            Ok(Action::Guard(guard))
         }
     }
 }
 
-struct Record {
-    time: Date,
-    act: Action,
+pub struct Record {
+    pub time: Date,
+    pub act: Action,
 }
 
 impl Record {
-    fn from_line(line: &str) -> Self {
-        Record {
-            time: Date::from_line(line),
-            act: Action::from_line(line),
-        }
+    pub fn from_line(line: &str) -> Result<Self, Error> {
+        Ok(Record {
+            time: Date::from_line(line)?,
+            act: Action::from_line(line)?,
+        })
     }
 }
 
@@ -102,13 +173,13 @@ mod test {
     #[test]
     fn get_date_from_line() {
         let line = "[1518-11-01 00:05] falls asleep";
-        let date = Date::from_line(line);
+        let date = Date::from_line(line).unwrap();
         let expected = Date {
             year: 1518,
             month: 11,
-            day: 01,
-            hour: 00,
-            minute: 05,
+            day: 1,
+            hour: 0,
+            minute: 5,
         };
         assert_eq!(expected, date);
     }
@@ -116,46 +187,87 @@ mod test {
     #[test]
     fn get_wake_action_from_line() {
         let line = "[1518-11-21 00:27] wakes up";
-        let action = Action::from_line(line);
+        let action = Action::from_line(line).unwrap();
         assert_eq!(Action::WakeUp, action);
     }
 
     #[test]
     fn get_sleep_action_from_line() {
         let line = "[1518-04-05 00:03] falls asleep";
-        let action = Action::from_line(line);
+        let action = Action::from_line(line).unwrap();
         assert_eq!(Action::Sleep, action);
     }
 
     #[test]
     fn get_guard_action_from_line() {
         let line = "[1518-11-07 23:59] Guard #683 begins shift";
-        let action = Action::from_line(line);
+        let action = Action::from_line(line).unwrap();
         assert_eq!(Action::Guard(683), action);
     }
 
     #[test]
-    fn part_1_example() {
+    fn bad_record_line() {
         let lines = vec![
             "[1518-11-01 00:00] Guard #10 begins shift",
-            "[1518-11-01 00:05] falls asleep          ",
-            "[1518-11-01 00:25] wakes up              ",
-            "[1518-11-01 00:30] falls asleep          ",
-            "[1518-11-01 00:55] wakes up              ",
+            "[1518-11-01 00:05] dozes off",
+        ];
+        assert_eq!(
+            Err(Error::parse(
+                "[1518-11-01 00:05] dozes off",
+                "\"wakes up\", \"falls asleep\" or \"Guard #<id> begins shift\""
+            ).at_line(2)),
+            parse_records(&lines).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn nobody_sleeps() {
+        let lines = vec!["[1518-11-01 00:00] Guard #10 begins shift"];
+        assert!(most_asleep_guard_minute(&parse_records(&lines).unwrap()).is_err());
+    }
+
+    #[test]
+    fn records_out_of_order() {
+        // the puzzle's example, newest record first
+        let mut lines = vec![
+            "[1518-11-01 00:00] Guard #10 begins shift",
+            "[1518-11-01 00:05] falls asleep",
+            "[1518-11-01 00:25] wakes up",
+            "[1518-11-01 00:30] falls asleep",
+            "[1518-11-01 00:55] wakes up",
             "[1518-11-01 23:58] Guard #99 begins shift",
-            "[1518-11-02 00:40] falls asleep          ",
-            "[1518-11-02 00:50] wakes up              ",
+            "[1518-11-02 00:40] falls asleep",
+            "[1518-11-02 00:50] wakes up",
             "[1518-11-03 00:05] Guard #10 begins shift",
-            "[1518-11-03 00:24] falls asleep          ",
-            "[1518-11-03 00:29] wakes up              ",
-            "[1518-11-04 00:02] Guard #99 begins shift",
-            "[1518-11-04 00:36] falls asleep          ",
-            "[1518-11-04 00:46] wakes up              ",
-            "[1518-11-05 00:03] Guard #99 begins shift",
-            "[1518-11-05 00:45] falls asleep          ",
-            "[1518-11-05 00:55] wakes up              ",
+            "[1518-11-03 00:24] falls asleep",
+            "[1518-11-03 00:29] wakes up",
+        ];
+        lines.reverse();
+        assert_eq!(Ok(240), most_asleep_guard_minute(&parse_records(&lines).unwrap()));
+    }
+
+    #[test]
+    fn wakes_before_any_shift() {
+        let lines = vec![
+            "[1518-11-01 00:05] falls asleep",
+            "[1518-11-01 00:25] wakes up",
         ];
+        assert_eq!(
+            Err(Error::no_answer("a guard woke up before any shift began")),
+            most_asleep_guard_minute(&parse_records(&lines).unwrap())
+        );
+    }
 
-        assert_eq!(240, most_asleep_guard_minute(&lines));
+    #[test]
+    fn wakes_before_falling_asleep() {
+        let lines = vec![
+            "[1518-11-01 00:00] Guard #10 begins shift",
+            "[1518-11-01 00:25] wakes up",
+            "[1518-11-01 00:30] falls asleep",
+        ];
+        assert_eq!(
+            Err(Error::no_answer("guard #10 woke up without falling asleep")),
+            most_asleep_guard_minute(&parse_records(&lines).unwrap())
+        );
     }
 }
@@ -1,22 +1,67 @@
-use std::io::{BufRead, BufReader, Read};
+use super::{solved, Part, Puzzle};
+use error::Error;
+use input;
 
-pub fn work<R: Read>(r: R) {
-    let reader = BufReader::new(r);
+pub struct Day05;
 
-    let lines: Vec<_> = reader.lines().map(|res| res.unwrap()).collect();
+const POLYMER: &str = "a polymer of letters on the first line";
 
-    println!("Part 1: {}", react_wrapper(&lines[0]));
-    println!("Part 2: {:?}", remove_and_react(&lines[0]));
+impl Puzzle for Day05 {
+    const DAY: u32 = 5;
+    const TITLE: &'static str = "Alchemical Reduction";
+
+    type Input = String;
+
+    fn parse(&self, lines: &[String]) -> Result<String, Error> {
+        let polymer = input::single_line(lines, POLYMER)?;
+        if let Some(err) = bad_units(&lines[0]).next() {
+            return Err(err);
+        }
+
+        Ok(polymer.to_string())
+    }
+
+    fn check(&self, lines: &[String]) -> Vec<Error> {
+        let mut errors: Vec<_> = lines.first().into_iter().flat_map(|l| bad_units(l)).collect();
+        errors.extend(input::check_single_line(lines, POLYMER));
+        errors
+    }
+
+    fn part1(&self, polymer: &String) -> Part {
+        solved(react_wrapper(polymer))
+    }
+
+    fn part2(&self, polymer: &String) -> Part {
+        let (unit, len) = remove_and_react(polymer);
+        debug!("Removing unit {} gives the shortest polymer", unit);
+        solved(len)
+    }
+}
+
+/// An error for each unit on the polymer's line that isn't a letter.
+///
+/// Each gives the unit's 1-based column rather than the whole line, which
+/// can be tens of thousands of units long.
+fn bad_units<'a>(line: &'a str) -> impl Iterator<Item = Error> + 'a {
+    let indent = line.chars().take_while(|c| c.is_whitespace()).count();
+    line.trim()
+        .chars()
+        .enumerate()
+        .filter(|&(_, c)| !c.is_ascii_alphabetic())
+        .map(move |(i, c)| {
+            Error::parse(c.to_string(), format!("a letter in column {}", indent + i + 1)).at_line(1)
+        })
 }
 
-fn react_wrapper(line: &str) -> usize {
+/// The length of the polymer once fully reacted.
+pub fn react_wrapper(line: &str) -> usize {
     let mut polymer: Vec<char> = line.chars().collect();
-    
+
     react(&mut polymer)
 }
 
 /// In place reacts the polymer, returning final size.
-fn react(polymer: &mut [char]) -> usize {
+pub fn react(polymer: &mut [char]) -> usize {
     let empty = '_'; // marker indicating removed items
     let mut last = empty;
     let mut last_i = 0;
@@ -48,12 +93,14 @@ fn react(polymer: &mut [char]) -> usize {
     polymer.iter().filter(|c| **c != empty).count()
 }
 
-fn remove_and_react(line: &str) -> (char, usize) {
+/// The unit type whose removal gives the shortest fully reacted polymer,
+/// along with that length.
+pub fn remove_and_react(line: &str) -> (char, usize) {
     let empty = '_';
     let polymer: Vec<char> = line.chars().collect();
     let mut results = Vec::new();
 
-    for typ in ('a' as u8)..=('z' as u8) {
+    for typ in b'a'..=b'z' {
         let typ_l = typ as char;
         let typ_u = typ_l.to_ascii_uppercase();
         let mut clone = polymer.clone();
@@ -66,7 +113,7 @@ fn remove_and_react(line: &str) -> (char, usize) {
         results.push((typ_l, react(&mut clone)));
     }
 
-    results.sort_by_key(|&(t, n)| n);
+    results.sort_by_key(|&(_, n)| n);
 
     results[0]
 }
@@ -75,21 +122,38 @@ fn remove_and_react(line: &str) -> (char, usize) {
 mod test {
     use super::*;
 
-    #[test]
-    fn polymer_reduction_example() {
-        let poly = "dabAcCaCBAcCcaDA";
-        assert_eq!(10, react_wrapper(&poly));
-    }
-
     #[test]
     fn mirrored_polymer() {
         let poly = "ZYXWVUTSRQPONMLKJIHGFEDCBAabcdefghijklmnopqrstuvwxyz";
-        assert_eq!(0, react_wrapper(&poly));
+        assert_eq!(0, react_wrapper(poly));
     }
 
     #[test]
     fn polymer_remove_reduce_example() {
         let poly = "dabAcCaCBAcCcaDA";
-        assert_eq!(('c', 4), remove_and_react(&poly));
+        assert_eq!(('c', 4), remove_and_react(poly));
+    }
+
+    #[test]
+    fn bad_unit_column() {
+        let lines = vec![" dabA1cC".to_string()];
+        assert_eq!(
+            Err(Error::parse("1", "a letter in column 6").at_line(1)),
+            Day05.parse(&lines)
+        );
+    }
+
+    #[test]
+    fn check_reports_every_problem() {
+        let lines: Vec<_> = ["abc1d2", "", "xx", "yy"].iter().map(|l| l.to_string()).collect();
+        assert_eq!(
+            vec![
+                Error::parse("1", "a letter in column 4").at_line(1),
+                Error::parse("2", "a letter in column 6").at_line(1),
+                Error::parse("xx", "nothing after the first line").at_line(3),
+                Error::parse("yy", "nothing after the first line").at_line(4),
+            ],
+            Day05.check(&lines)
+        );
     }
 }
@@ -1,111 +1,72 @@
-use std::io::{BufRead, BufReader, Read};
 use std::ops::Deref;
-use std::collections::{HashMap, HashSet};
 
 use regex::Regex;
 
-pub fn work<R: Read>(r: R) {
-    let reader = BufReader::new(r);
+use super::{attempted, Part, Puzzle};
+use error::Error;
+use graph::{Graph, TieBreak};
+use input;
 
-    let lines: Vec<_> = reader.lines().map(|res| res.unwrap()).collect();
+pub struct Day07;
 
-    println!("Part 1: {}", do_work(&lines));
-}
-
-fn do_work<T: Deref<Target = str>>(lines: &[T]) -> String {
-    let parsed = parse_input(lines);
-    solve(parsed)
-}
+impl Puzzle for Day07 {
+    const DAY: u32 = 7;
+    const TITLE: &'static str = "The Sum of Its Parts";
 
-fn parse_input<T: Deref<Target = str>>(lines: &[T]) -> HashMap<char, Vec<char>> {
-    let re = Regex::new(r"Step ([A-Z]) must be finished before step ([A-Z]) can begin.").unwrap();
-    let mut nodes = HashMap::new();
+    type Input = Graph<char>;
 
-    for l in lines {
-        let caps = re.captures(l).unwrap();
-        let pre_req = caps[1].as_bytes()[0] as char;
-        let node = caps[2].as_bytes()[0] as char;
+    fn parse(&self, lines: &[String]) -> Result<Graph<char>, Error> {
+        parse_input(lines)
+    }
 
-        let n = nodes.entry(node).or_insert(vec![]);
-        n.push(pre_req);
+    fn check(&self, lines: &[String]) -> Vec<Error> {
+        input::check_lines_with(lines, edge_parser())
     }
 
-    nodes
+    fn part1(&self, steps: &Graph<char>) -> Part {
+        attempted(solve(steps))
+    }
 }
 
-fn solve(work: HashMap<char, Vec<char>>) -> String {
-    let mut order = String::new();
-    
-    // find root nodes
-    let mut done: HashSet<char> = HashSet::new();
-    {
-        let mut not_found = HashSet::new();
-        let mut found = HashSet::new();
-        for (k, v) in work.iter() {
-            not_found.remove(k);
-            found.insert(k);
-            for c in v {
-                if !found.contains(c) {
-                    not_found.insert(*c);
-                }
-            }
-        }
-
-        not_found.iter().map(|c| done.insert(*c));
-    }
+/// The steps, with an edge from each step to those waiting for it.
+pub fn parse_input<T: Deref<Target = str>>(lines: &[T]) -> Result<Graph<char>, Error> {
+    let edges = input::parse_lines_with(lines, edge_parser())?;
 
-    // add nodes in alphabetical order
-    {
-        let mut sorted = done.iter().map(|c| *c).collect::<Vec<char>>();
-        sorted.sort();
-        for c in &sorted {
-            order.push(*c);
-        }
+    let mut steps = Graph::new();
+    for (before, after) in edges {
+        steps.add_edge(before, after);
     }
 
-    // begin solving
-    let mut available = HashSet::new();
-    'outer: loop {
-        // find available nodes
-        // TODO replace this with hashset operations?
-        // done ^ available?
-        for (k, v) in work.iter() {
-            if done.contains(k) {
-                continue;
-            }
- 
-            for req in v {
-                if !done.contains(req) {
-                    continue 'outer;
-                }
-            }
-
-            available.insert(k);
-        }
-
-        if available.is_empty() {
-            break;
-        }
-
-        // add nodes in alphabetical order
-        {
-            let mut sorted = available.drain().map(|c| *c).collect::<Vec<char>>();
-            sorted.sort();
-            for c in &sorted {
-                order.push(*c)
-            }
-        }
-
-        available.clear()
+    Ok(steps)
+}
+
+/// Parses a line into the step that must be finished first and the step
+/// waiting for it.
+fn edge_parser() -> impl Fn(&str) -> Result<(char, char), Error> {
+    let re = Regex::new(r"Step ([A-Z]) must be finished before step ([A-Z]) can begin.").unwrap();
+    move |l| {
+        let caps = re.captures(l).ok_or_else(|| {
+            Error::parse(l, "\"Step <A-Z> must be finished before step <A-Z> can begin.\"")
+        })?;
+        Ok((caps[1].as_bytes()[0] as char, caps[2].as_bytes()[0] as char))
     }
+}
+
+/// The order steps are done in, picking alphabetically among those ready.
+pub fn solve(steps: &Graph<char>) -> Result<String, Error> {
+    let order = steps.topological_sort(TieBreak::Lexicographic).map_err(|cycle| {
+        Error::no_answer(format!(
+            "steps {} depend on each other and can never start",
+            cycle
+        ))
+    })?;
 
-    order
+    Ok(order.into_iter().collect())
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::collections::HashMap;
 
     #[test]
     fn part_1_example_parse() {
@@ -119,30 +80,58 @@ mod test {
             "Step F must be finished before step E can begin.",
         ];
 
-        let mut expected = HashMap::new();
-        expected.insert('A', vec!['C']);
-        expected.insert('F', vec!['C']);
-        expected.insert('B', vec!['A']);
-        expected.insert('D', vec!['A']);
-        expected.insert('E', vec!['B', 'D', 'F']);
+        let steps = parse_input(&example).unwrap();
+        assert_eq!("ABCDEF", steps.nodes().collect::<String>());
+        assert_eq!("AF", steps.dependents(&'C').collect::<String>());
+        assert_eq!("BDF", steps.dependencies(&'E').collect::<String>());
+        assert_eq!("", steps.dependencies(&'C').collect::<String>());
+    }
+
+    #[test]
+    fn one_step_at_a_time() {
+        // B becomes ready as soon as A is done and comes before C, rather
+        // than waiting for every step that was ready alongside A
+        let example = vec![
+            "Step A must be finished before step B can begin.",
+            "Step C must be finished before step D can begin.",
+        ];
+
+        let steps = parse_input(&example).unwrap();
+        assert_eq!(Ok("ABCD".to_string()), solve(&steps));
+    }
+
+    #[test]
+    fn roots_only_named_as_requirements() {
+        let example = vec![
+            "Step Z must be finished before step A can begin.",
+            "Step B must be finished before step A can begin.",
+        ];
 
-        assert_eq!(expected, parse_input(&example));
+        let steps = parse_input(&example).unwrap();
+        assert_eq!(Ok("BZA".to_string()), solve(&steps));
     }
 
     #[test]
-    fn part_1_example_solve() {
+    fn cyclic_steps() {
         let example = vec![
-            "Step C must be finished before step A can begin.",
-            "Step C must be finished before step F can begin.",
             "Step A must be finished before step B can begin.",
-            "Step A must be finished before step D can begin.",
-            "Step B must be finished before step E can begin.",
-            "Step D must be finished before step E can begin.",
-            "Step F must be finished before step E can begin.",
+            "Step B must be finished before step A can begin.",
         ];
 
-        let expected = "CABDFE";
+        let parsed = parse_input(&example).unwrap();
+        assert_eq!(
+            "steps A -> B -> A depend on each other and can never start",
+            solve(&parsed).unwrap_err().to_string()
+        );
+    }
 
-        assert_eq!(expected, do_work(&example));
+    #[test]
+    fn bad_step_line() {
+        let example = vec!["Step C must be finished before step A can begin.", "Step C"];
+        let err = parse_input(&example).unwrap_err();
+        assert_eq!(
+            Error::parse("Step C", "\"Step <A-Z> must be finished before step <A-Z> can begin.\"").at_line(2),
+            err
+        );
     }
 }
@@ -4,3 +4,339 @@ pub mod day03;
 pub mod day04;
 pub mod day05;
 pub mod day07;
+
+// not a real day, included to check that new days start out compiling
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../template.rs"]
+mod template;
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use error::Error;
+
+/// The outcome of solving a single part.
+///
+/// `None` means the part has not been implemented yet, otherwise it holds
+/// either the answer or the reason it could not be found.
+pub type Part = Option<Result<String, Error>>;
+
+/// Both parts' answers for a day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Answer {
+    pub part1: Part,
+    pub part2: Part,
+}
+
+/// Wrap a successfully found answer.
+pub fn solved<T: ToString>(answer: T) -> Part {
+    Some(Ok(answer.to_string()))
+}
+
+/// Wrap the outcome of a solver that may fail.
+pub fn attempted<T: ToString>(result: Result<T, Error>) -> Part {
+    Some(result.map(|answer| answer.to_string()))
+}
+
+/// Displays a single part for a human reader.
+pub struct DisplayPart<'a>(pub &'a Part);
+
+impl<'a> fmt::Display for DisplayPart<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(Ok(answer)) => write!(f, "{}", answer),
+            Some(Err(err)) => write!(f, "failed: {}", err),
+            None => write!(f, "not implemented"),
+        }
+    }
+}
+
+/// Which parts of a day to solve.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Parts {
+    Both,
+    Part1,
+    Part2,
+}
+
+impl Parts {
+    /// Whether `part`, either 1 or 2, should be solved.
+    pub fn includes(self, part: u8) -> bool {
+        match self {
+            Parts::Both => true,
+            Parts::Part1 => part == 1,
+            Parts::Part2 => part == 2,
+        }
+    }
+}
+
+impl FromStr for Parts {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "1" => Ok(Parts::Part1),
+            "2" => Ok(Parts::Part2),
+            "both" => Ok(Parts::Both),
+            _ => Err(format!("part must be 1, 2 or both, got {:?}", s)),
+        }
+    }
+}
+
+/// How long each stage of solving a day took.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Timings {
+    pub parse: Duration,
+    pub part1: Duration,
+    pub part2: Duration,
+}
+
+impl Timings {
+    pub fn total(&self) -> Duration {
+        self.parse + self.part1 + self.part2
+    }
+}
+
+/// A single day's puzzle, as seen by the runner.
+///
+/// Days don't implement this directly, they implement `Puzzle` and are
+/// listed in `all` so they can be looked up by number.
+pub trait Solution {
+    /// The day of the advent calendar this solves.
+    fn day(&self) -> u32;
+
+    /// The puzzle's title.
+    fn title(&self) -> &'static str;
+
+    fn part1(&self, lines: &[String]) -> Part;
+
+    fn part2(&self, _lines: &[String]) -> Part {
+        None
+    }
+
+    /// Every problem found in the input, without solving anything.
+    ///
+    /// Solutions without a grammar to check accept anything.
+    fn check(&self, _lines: &[String]) -> Vec<Error> {
+        Vec::new()
+    }
+
+    /// Solve the selected parts, timing each stage.
+    ///
+    /// Parts that aren't selected are left as `None` and take no time.
+    /// Solutions without a separate parsing stage report it as taking no time.
+    fn timed(&self, lines: &[String], parts: Parts) -> (Answer, Timings) {
+        let mut timings = Timings::default();
+        let mut part1 = None;
+        let mut part2 = None;
+        if parts.includes(1) {
+            part1 = time(&mut timings.part1, || self.part1(lines));
+        }
+        if parts.includes(2) {
+            part2 = time(&mut timings.part2, || self.part2(lines));
+        }
+
+        (Answer { part1, part2 }, timings)
+    }
+}
+
+/// A day's puzzle, with its input parsed once and shared by both parts.
+pub trait Puzzle {
+    const DAY: u32;
+    const TITLE: &'static str;
+
+    /// The parsed form of the puzzle input.
+    type Input;
+
+    fn parse(&self, lines: &[String]) -> Result<Self::Input, Error>;
+
+    /// Every problem with the input, without solving anything.
+    ///
+    /// Defaults to the first error `parse` finds, days override it to report
+    /// every bad line.
+    fn check(&self, lines: &[String]) -> Vec<Error> {
+        self.parse(lines).err().into_iter().collect()
+    }
+
+    fn part1(&self, input: &Self::Input) -> Part;
+
+    fn part2(&self, _input: &Self::Input) -> Part {
+        None
+    }
+}
+
+impl<P: Puzzle> Solution for P {
+    fn day(&self) -> u32 {
+        P::DAY
+    }
+
+    fn title(&self) -> &'static str {
+        P::TITLE
+    }
+
+    fn part1(&self, lines: &[String]) -> Part {
+        let part = match self.parse(lines) {
+            Ok(input) => Puzzle::part1(self, &input),
+            Err(err) => Some(Err(err)),
+        };
+        in_day(part, P::DAY)
+    }
+
+    fn part2(&self, lines: &[String]) -> Part {
+        let part = match self.parse(lines) {
+            Ok(input) => Puzzle::part2(self, &input),
+            Err(err) => Some(Err(err)),
+        };
+        in_day(part, P::DAY)
+    }
+
+    fn check(&self, lines: &[String]) -> Vec<Error> {
+        Puzzle::check(self, lines)
+            .into_iter()
+            .map(|e| e.in_day(P::DAY))
+            .collect()
+    }
+
+    fn timed(&self, lines: &[String], parts: Parts) -> (Answer, Timings) {
+        let mut timings = Timings::default();
+        let mut answer = Answer {
+            part1: None,
+            part2: None,
+        };
+        match time(&mut timings.parse, || self.parse(lines)) {
+            Ok(input) => {
+                if parts.includes(1) {
+                    answer.part1 = time(&mut timings.part1, || Puzzle::part1(self, &input));
+                }
+                if parts.includes(2) {
+                    answer.part2 = time(&mut timings.part2, || Puzzle::part2(self, &input));
+                }
+            }
+            Err(err) => {
+                if parts.includes(1) {
+                    answer.part1 = Some(Err(err.clone()));
+                }
+                if parts.includes(2) {
+                    answer.part2 = Some(Err(err));
+                }
+            }
+        }
+
+        (
+            Answer {
+                part1: in_day(answer.part1, P::DAY),
+                part2: in_day(answer.part2, P::DAY),
+            },
+            timings,
+        )
+    }
+}
+
+/// Tag any error in `part` with the day it came from.
+fn in_day(part: Part, day: u32) -> Part {
+    part.map(|r| r.map_err(|e| e.in_day(day)))
+}
+
+/// Run `f`, storing how long it took in `elapsed`.
+fn time<T, F: FnOnce() -> T>(elapsed: &mut Duration, f: F) -> T {
+    let start = Instant::now();
+    let result = f();
+    *elapsed = start.elapsed();
+    result
+}
+
+/// Every implemented day, in order.
+pub fn all() -> Vec<&'static dyn Solution> {
+    vec![
+        &day01::Day01,
+        &day02::Day02,
+        &day03::Day03,
+        &day04::Day04,
+        &day05::Day05,
+        &day07::Day07,
+    ]
+}
+
+/// Look up the solution for `day`, if one has been implemented.
+pub fn get(day: u32) -> Option<&'static dyn Solution> {
+    all().into_iter().find(|s| s.day() == day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn registry_is_ordered_and_unique() {
+        let days: Vec<_> = all().iter().map(|s| s.day()).collect();
+        let mut sorted = days.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(sorted, days);
+    }
+
+    #[test]
+    fn display_parts() {
+        assert_eq!("42", DisplayPart(&solved(42)).to_string());
+        assert_eq!(
+            "failed: bad input",
+            DisplayPart(&Some(Err(Error::no_answer("bad input")))).to_string()
+        );
+        assert_eq!("not implemented", DisplayPart(&None).to_string());
+    }
+
+    #[test]
+    fn parse_errors_fail_both_parts() {
+        let lines = vec!["+1".to_string(), "nope".to_string()];
+        let (answer, _) = day01::Day01.timed(&lines, Parts::Both);
+        let expected = Error::parse("nope", "a frequency change like +1 or -2")
+            .at_line(2)
+            .in_day(1);
+        assert_eq!(Some(Err(expected.clone())), answer.part1);
+        assert_eq!(Some(Err(expected)), answer.part2);
+    }
+
+    #[test]
+    fn timed_skips_unselected_parts() {
+        let lines: Vec<_> = ["+3", "+3", "+4", "-2", "-4"].iter().map(|l| l.to_string()).collect();
+        let (answer, timings) = day01::Day01.timed(&lines, Parts::Part2);
+        assert_eq!(None, answer.part1);
+        assert_eq!(solved(10), answer.part2);
+        assert_eq!(Duration::default(), timings.part1);
+
+        let (answer, _) = day01::Day01.timed(&["x".to_string()], Parts::Part1);
+        assert!(answer.part1.unwrap().is_err());
+        assert_eq!(None, answer.part2);
+    }
+
+    #[test]
+    fn check_reports_every_line() {
+        let lines: Vec<_> = ["+1", "one", "-2", "+"].iter().map(|l| l.to_string()).collect();
+        let lines: Vec<_> = Solution::check(&day01::Day01, &lines)
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                r#"day 1 line 2: expected a frequency change like +1 or -2, got "one""#,
+                r#"day 1 line 4: expected a frequency change like +1 or -2, got "+""#,
+            ],
+            lines
+        );
+    }
+
+    #[test]
+    fn parse_parts() {
+        assert_eq!(Ok(Parts::Part1), "1".parse());
+        assert_eq!(Ok(Parts::Both), "both".parse());
+        assert!("3".parse::<Parts>().is_err());
+    }
+
+    #[test]
+    fn get_missing_day() {
+        assert!(get(6).is_none());
+        assert_eq!(Some(7), get(7).map(|s| s.day()));
+    }
+}
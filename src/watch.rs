@@ -0,0 +1,86 @@
+//! Noticing when files change, by polling their size and modification time.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// How often watched files are checked.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// What a file looked like when last checked, `None` if it didn't exist.
+type Stamp = Option<(SystemTime, u64)>;
+
+fn stamp(path: &Path) -> Stamp {
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+/// Watches a set of files for changes.
+#[derive(Debug)]
+pub struct Watcher {
+    files: Vec<(PathBuf, Stamp)>,
+}
+
+impl Watcher {
+    /// Watch `paths`, treating their current state as unchanged.
+    pub fn new<P: AsRef<Path>>(paths: &[P]) -> Self {
+        Watcher {
+            files: paths
+                .iter()
+                .map(|p| (p.as_ref().to_path_buf(), stamp(p.as_ref())))
+                .collect(),
+        }
+    }
+
+    /// The files that have changed, been created or been removed since the
+    /// last check.
+    pub fn changed(&mut self) -> Vec<&Path> {
+        let mut changed = Vec::new();
+        for (path, last) in &mut self.files {
+            let now = stamp(path);
+            if now != *last {
+                *last = now;
+                changed.push(path.as_path());
+            }
+        }
+        changed
+    }
+
+    /// Block until at least one file changes, returning those that did.
+    pub fn wait(&mut self) -> Vec<PathBuf> {
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let changed: Vec<_> = self.changed().into_iter().map(Path::to_path_buf).collect();
+            if !changed.is_empty() {
+                return changed;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env;
+    use std::process;
+
+    #[test]
+    fn notices_changes() {
+        let path = env::temp_dir().join(format!("aoc_2018_watch_{}.txt", process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut watcher = Watcher::new(&[&path]);
+        assert!(watcher.changed().is_empty());
+
+        fs::write(&path, "+1\n").unwrap();
+        assert_eq!(vec![path.as_path()], watcher.changed());
+        assert!(watcher.changed().is_empty());
+
+        fs::write(&path, "+1\n-2\n").unwrap();
+        assert_eq!(1, watcher.changed().len());
+
+        fs::remove_file(&path).unwrap();
+        assert_eq!(1, watcher.changed().len());
+    }
+}
@@ -1,18 +1,23 @@
-use std::io::{BufRead, BufReader, Read};
-use std::ops::Deref;
+use super::{Part, Puzzle};
+use error::Error;
 
-pub fn work<R: Read>(r: R) {
-    let reader = BufReader::new(r);
+pub struct DayNN;
 
-    let lines: Vec<_> = reader.lines().map(|res| res.unwrap()).collect();
+impl Puzzle for DayNN {
+    const DAY: u32 = 0;
+    const TITLE: &'static str = "TITLE";
 
-    println!("Part 1: {}", do_work(&lines));
-}
+    type Input = Vec<String>;
 
-fn do_work<T: Deref<Target = str>>(lines: &[T]) -> usize {
-}
+    fn parse(&self, lines: &[String]) -> Result<Vec<String>, Error> {
+        Ok(lines.to_vec())
+    }
+
+    fn part1(&self, _input: &Vec<String>) -> Part {
+        None
+    }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+    fn part2(&self, _input: &Vec<String>) -> Part {
+        None
+    }
 }
@@ -0,0 +1,136 @@
+//! Tests of the command line, run against the built binary.
+
+extern crate aoc_2018;
+
+use std::env;
+use std::fs;
+use std::process::{self, Command};
+
+use aoc_2018::solutions;
+
+/// Whether `text` holds exactly one JSON value.
+///
+/// Only the syntax is checked, which is all that's needed to tell results
+/// from anything else that ends up on stdout.
+fn is_json(text: &str) -> bool {
+    match value(text) {
+        Ok(rest) => rest.trim().is_empty(),
+        Err(_) => false,
+    }
+}
+
+/// Skip past the JSON value at the start of `s`, returning what follows it.
+fn value(s: &str) -> Result<&str, String> {
+    let s = s.trim_start();
+    match s.chars().next() {
+        Some('{') => sequence(&s[1..], '}', member),
+        Some('[') => sequence(&s[1..], ']', value),
+        Some('"') => string(s),
+        Some(_) => literal(s),
+        None => Err("expected a value, got nothing".to_string()),
+    }
+}
+
+/// The items of an object or array up to and including `close`.
+fn sequence(s: &str, close: char, item: fn(&str) -> Result<&str, String>) -> Result<&str, String> {
+    let mut s = s.trim_start();
+    if let Some(rest) = s.strip_prefix(close) {
+        return Ok(rest);
+    }
+    loop {
+        s = item(s)?.trim_start();
+        if let Some(rest) = s.strip_prefix(',') {
+            s = rest;
+        } else if let Some(rest) = s.strip_prefix(close) {
+            return Ok(rest);
+        } else {
+            return Err(format!("expected ',' or '{}', got {:?}", close, s));
+        }
+    }
+}
+
+/// A `"key": value` pair in an object.
+fn member(s: &str) -> Result<&str, String> {
+    let s = string(s.trim_start())?.trim_start();
+    match s.strip_prefix(':') {
+        Some(rest) => value(rest),
+        None => Err(format!("expected ':', got {:?}", s)),
+    }
+}
+
+fn string(s: &str) -> Result<&str, String> {
+    let body = s
+        .strip_prefix('"')
+        .ok_or_else(|| format!("expected a string, got {:?}", s))?;
+    let mut escaped = false;
+    for (i, c) in body.char_indices() {
+        if escaped {
+            if !"\"\\/bfnrtu".contains(c) {
+                return Err(format!("unknown escape \\{} in {:?}", c, s));
+            }
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            return Ok(&body[i + 1..]);
+        } else if (c as u32) < 0x20 {
+            return Err(format!("unescaped control character in {:?}", s));
+        }
+    }
+    Err(format!("unterminated string {:?}", s))
+}
+
+/// `null`, `true`, `false` or a number.
+fn literal(s: &str) -> Result<&str, String> {
+    for word in &["null", "true", "false"] {
+        if let Some(rest) = s.strip_prefix(word) {
+            return Ok(rest);
+        }
+    }
+    let end = s
+        .find(|c: char| !(c.is_ascii_digit() || "-+.eE".contains(c)))
+        .unwrap_or(s.len());
+    if end > 0 && !s.starts_with('+') && s[..end].parse::<f64>().is_ok() {
+        Ok(&s[end..])
+    } else {
+        Err(format!("expected a value, got {:?}", s))
+    }
+}
+
+#[test]
+fn json_checker() {
+    assert!(is_json(
+        r#"{"day":1,"part1":{"answer":"a \"b\""},"list":[1,-2.5e3,null,true]}"#
+    ));
+    assert!(is_json("[]"));
+    assert!(!is_json("12:31:18 [INFO] Running day 1"));
+    assert!(!is_json(r#"{"day":1}{"day":2}"#));
+    assert!(!is_json(r#"{"day":1,}"#));
+    assert!(!is_json(r#"{"day"}"#));
+}
+
+#[test]
+fn json_output_is_only_json() {
+    // with no inputs to read every day fails straight away, but is still
+    // logged as it's run
+    let dir = env::temp_dir().join(format!("aoc_2018_cli_{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc_2018"))
+        .arg("all")
+        .arg("--format")
+        .arg("json")
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<_> = stdout.lines().collect();
+    assert_eq!(solutions::all().len(), lines.len(), "{}", stdout);
+    for line in lines {
+        assert!(is_json(line), "not JSON: {}", line);
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Running day 1"), "{}", stderr);
+}
@@ -0,0 +1,135 @@
+//! The puzzle examples, kept as fixture files and checked against every
+//! solver.
+//!
+//! Each example is a pair of files in a directory per day, e.g.
+//! `tests/fixtures/day03/claims.txt` holding the input and
+//! `tests/fixtures/day03/claims.answers` holding the expected answers in the
+//! same `<day> <part> <answer>` form as `answers.txt`. Parts without an
+//! expected answer aren't run, since examples given for one part often never
+//! finish for the other.
+//!
+//! They live under `tests/` rather than `examples/`, which Cargo keeps for
+//! example programs.
+
+extern crate aoc_2018;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use aoc_2018::answers::{Answers, Verification};
+use aoc_2018::solutions::{self, Parts};
+use aoc_2018::{input, runner};
+
+/// A single example input and the answers it should give.
+struct Example {
+    day: u32,
+    name: String,
+    input: PathBuf,
+    answers: Answers,
+}
+
+impl Example {
+    /// Load the example whose input is at `input`, along with the
+    /// `.answers` file next to it.
+    fn load(day: u32, input: &Path) -> Result<Self, String> {
+        let name = input
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let path = input.with_extension("answers");
+        let answers = Answers::load(&path)?;
+        if answers.get(day, 1).is_none() && answers.get(day, 2).is_none() {
+            return Err(format!("{} has no answers for day {}", path.display(), day));
+        }
+
+        Ok(Example {
+            day,
+            name,
+            input: input.to_path_buf(),
+            answers,
+        })
+    }
+
+    /// The parts with an expected answer.
+    fn parts(&self) -> Parts {
+        match (self.answers.get(self.day, 1), self.answers.get(self.day, 2)) {
+            (Some(_), None) => Parts::Part1,
+            (None, Some(_)) => Parts::Part2,
+            _ => Parts::Both,
+        }
+    }
+
+    /// Run the day's solver on the example and compare its answers.
+    fn check(&self) -> Result<Verification, String> {
+        let solution = solutions::get(self.day)
+            .ok_or_else(|| format!("day {} is not implemented", self.day))?;
+        let lines = input::read_file(&self.input).map_err(|e| e.to_string())?;
+        let run = runner::run(solution, &lines, self.parts());
+        Ok(self.answers.verify(&[run]))
+    }
+}
+
+/// Every example under `dir`, ordered by day and then name.
+///
+/// Only directories named like `day03` and files ending in `.txt` are
+/// considered, anything else is left alone.
+fn discover(dir: &Path) -> Result<Vec<Example>, String> {
+    let mut examples = Vec::new();
+    for (day, day_dir) in entries(dir)? {
+        let day = match day.strip_prefix("day").and_then(|d| d.parse().ok()) {
+            Some(day) if day_dir.is_dir() => day,
+            _ => continue,
+        };
+        for (_, path) in entries(&day_dir)? {
+            if path.extension().is_some_and(|ext| ext == "txt") {
+                examples.push(Example::load(day, &path)?);
+            }
+        }
+    }
+
+    examples.sort_by(|a, b| (a.day, &a.name).cmp(&(b.day, &b.name)));
+    Ok(examples)
+}
+
+/// The names and paths of everything in `dir`.
+fn entries(dir: &Path) -> Result<Vec<(String, PathBuf)>, String> {
+    let read_error = |e| format!("could not read {}: {}", dir.display(), e);
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(dir).map_err(read_error)? {
+        let entry = entry.map_err(read_error)?;
+        entries.push((entry.file_name().to_string_lossy().into_owned(), entry.path()));
+    }
+    Ok(entries)
+}
+
+#[test]
+fn every_example_passes() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let examples = discover(&dir).unwrap();
+    assert!(!examples.is_empty());
+
+    let mut failures = Vec::new();
+    for example in &examples {
+        match example.check() {
+            Ok(ref verification) if verification.passed() => {}
+            Ok(verification) => {
+                for mismatch in verification.mismatches {
+                    failures.push(format!("{}: {}", example.name, mismatch));
+                }
+            }
+            Err(err) => failures.push(format!("{}: {}", example.name, err)),
+        }
+    }
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+fn answers_select_parts() {
+    let example = Example {
+        day: 3,
+        name: "claims".to_string(),
+        input: PathBuf::from("claims.txt"),
+        answers: Answers::parse("# comment\n3 1 4\n").unwrap(),
+    };
+    assert_eq!(Parts::Part1, example.parts());
+}